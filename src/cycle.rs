@@ -0,0 +1,185 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Cycle detection for states that are repeatedly updated by a function
+//!
+//! brent and floyd only keep a couple of states, but must compare whole
+//! states. find_hashed remembers every state, and find_by_key only a key
+//! of each, like its fingerprint.
+
+use hashbrown::hash_map::Entry;
+use hashbrown::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Where the states start repeating
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cycle {
+    /// The first step in the cycle, also called mu
+    pub start: usize,
+    /// The number of steps in the cycle, also called lambda
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The first step with the same state as step n
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    /// The state after n steps from start, without taking all the steps
+    pub fn nth<S: Clone>(&self, start: &S, mut f: impl FnMut(&S) -> S, n: usize) -> S {
+        let mut state = start.clone();
+        for _ in 0..self.equivalent_step(n) {
+            state = f(&state);
+        }
+        state
+    }
+}
+
+/// Finds the cycle with Brent's algorithm
+pub fn brent<S: Clone + Eq>(start: &S, mut f: impl FnMut(&S) -> S) -> Cycle {
+    // Find the length with the tortoise waiting at powers of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = start.clone();
+    let mut hare = f(start);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = f(&hare);
+        length += 1;
+    }
+
+    // Then the start, with the hare length steps ahead
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    for _ in 0..length {
+        hare = f(&hare);
+    }
+    let mut mu = 0;
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        mu += 1;
+    }
+    Cycle { start: mu, length }
+}
+
+/// Finds the cycle with Floyd's tortoise and hare algorithm
+pub fn floyd<S: Clone + Eq>(start: &S, mut f: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = f(start);
+    let mut hare = f(&tortoise);
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        let next = f(&hare);
+        hare = f(&next);
+    }
+
+    let mut mu = 0;
+    tortoise = start.clone();
+    while tortoise != hare {
+        tortoise = f(&tortoise);
+        hare = f(&hare);
+        mu += 1;
+    }
+
+    let mut length = 1;
+    hare = f(&tortoise);
+    while tortoise != hare {
+        hare = f(&hare);
+        length += 1;
+    }
+    Cycle { start: mu, length }
+}
+
+/// Finds the cycle by remembering the key of every state
+///
+/// Different states must have different keys, or the cycle can be wrong.
+pub fn find_by_key<S: Clone, K: Hash + Eq>(
+    start: &S,
+    mut f: impl FnMut(&S) -> S,
+    mut key: impl FnMut(&S) -> K,
+) -> Cycle {
+    let mut seen = HashMap::new();
+    let mut state = start.clone();
+    for step in 0.. {
+        match seen.entry(key(&state)) {
+            Entry::Occupied(first) => {
+                return Cycle {
+                    start: *first.get(),
+                    length: step - first.get(),
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(step);
+            }
+        }
+        state = f(&state);
+    }
+    unreachable!("The states repeat")
+}
+
+/// Finds the cycle by remembering every state
+pub fn find_hashed<S: Clone + Hash + Eq>(start: &S, f: impl FnMut(&S) -> S) -> Cycle {
+    find_by_key(start, f, S::clone)
+}
+
+/// A 64 bit hash of state, to use as its key
+///
+/// Different states can get the same fingerprint, so only use it where a
+/// rare wrong cycle is acceptable.
+pub fn fingerprint<S: Hash>(state: &S) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    state.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn f(x: &u32) -> u32 {
+        (x * x + 1) % 255
+    }
+
+    #[test]
+    fn all_find_the_same_cycle() {
+        for start in 0..255 {
+            let cycle = brent(&start, f);
+            assert_eq!(floyd(&start, f), cycle);
+            assert_eq!(find_hashed(&start, f), cycle);
+            assert_eq!(find_by_key(&start, f, fingerprint), cycle);
+
+            let mut state = start;
+            for n in 0..100 {
+                assert_eq!(cycle.nth(&start, f, n), state);
+                state = f(&state);
+            }
+        }
+    }
+
+    #[test]
+    fn start_and_length() {
+        // 0, 1, 2, 3, 4, 2, 3, 4...
+        let next = |x: &u8| if *x == 4 { 2 } else { x + 1 };
+        let cycle = brent(&0, next);
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 2,
+                length: 3
+            }
+        );
+        assert_eq!(cycle.equivalent_step(1), 1);
+        assert_eq!(cycle.equivalent_step(1_000_000_000), 4);
+        assert_eq!(cycle.nth(&0, next, 1_000_000_000), 4);
+    }
+}
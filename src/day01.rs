@@ -4,16 +4,25 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use super::parse::ParseError;
+
 type InputType = String;
 type SolutionType = i32;
 
 #[aoc_generator(day1)]
-pub fn input_generator(input: &str) -> Vec<InputType> {
+pub fn input_generator(input: &str) -> Result<Vec<InputType>, ParseError> {
     let mut result = vec![];
     for line in input.lines() {
+        if let Some(pos) = line.find(|c: char| !c.is_ascii_alphanumeric()) {
+            return Err(ParseError::new(
+                input,
+                &line[pos..],
+                "only letters and digits",
+            ));
+        }
         result.push(line.to_string());
     }
-    result
+    Ok(result)
 }
 
 #[aoc(day1, part1)]
@@ -28,7 +37,7 @@ pub fn solve_part1(data: &[InputType]) -> SolutionType {
             let last_digit = line
                 .chars()
                 .filter_map(|c| c.to_digit(10))
-                .last()
+                .next_back()
                 .expect("No last digit");
             (first_digit * 10 + last_digit) as SolutionType
         })
@@ -61,7 +70,7 @@ pub fn solve_part2(data: &[InputType]) -> SolutionType {
             let last_digit = line
                 .chars()
                 .filter_map(|c| c.to_digit(10))
-                .last()
+                .next_back()
                 .expect("No last digit");
             (first_digit * 10 + last_digit) as SolutionType
         })
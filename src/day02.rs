@@ -4,16 +4,18 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use super::parse::{self, ParseError};
+
 type InputType = Vec<(usize, usize, usize)>;
 type SolutionType = usize;
 
 #[aoc_generator(day2)]
-pub fn input_generator(input: &str) -> Vec<InputType> {
+pub fn input_generator(input: &str) -> Result<Vec<InputType>, ParseError> {
     // Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
     input
         .lines()
-        .map(|line| line.split_once(": ").expect("No game separator").1)
-        .map(|game| {
+        .map(|line| {
+            let game = parse::header(input, line)?.1;
             // 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
             game.split("; ")
                 .map(|round| {
@@ -24,19 +26,18 @@ pub fn input_generator(input: &str) -> Vec<InputType> {
                     let mut green = 0;
                     let mut blue = 0;
                     for cubes in round.split(", ") {
-                        let cubes = cubes
-                            .split_once(' ')
-                            .expect("space between number and type");
-                        let n_cubes: usize = cubes.0.parse().expect("integer");
-                        if cubes.1 == "red" {
-                            red = n_cubes;
-                        } else if cubes.1 == "green" {
-                            green = n_cubes;
-                        } else if cubes.1 == "blue" {
-                            blue = n_cubes;
+                        let cubes = parse::split_once(input, cubes, " ")?;
+                        let n_cubes: usize = parse::number(input, cubes.0)?;
+                        match cubes.1 {
+                            "red" => red = n_cubes,
+                            "green" => green = n_cubes,
+                            "blue" => blue = n_cubes,
+                            colour => {
+                                return Err(ParseError::new(input, colour, "red, green or blue"))
+                            }
                         }
                     }
-                    (red, green, blue)
+                    Ok((red, green, blue))
                 })
                 .collect()
         })
@@ -4,15 +4,16 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use super::parse::{self, ParseError};
 use super::world::*;
 
 type SolutionType = usize;
 
 #[aoc_generator(day3)]
-pub fn input_generator(input: &str) -> Map {
+pub fn input_generator(input: &str) -> Result<Map, ParseError> {
     // 467..114..
     // ...*......
-    Map::from_string(input)
+    parse::map(input)
 }
 
 fn is_symbol(c: u8) -> bool {
@@ -43,7 +44,7 @@ pub fn solve_part1(map: &Map) -> SolutionType {
         } else {
             num = num * 10 + SolutionType::from(c - b'0');
             if !any_symbol {
-                for (_pos, _dir, c) in map.neighbors(pos) {
+                for (_pos, _dir, c) in map.neighbors8(pos) {
                     any_symbol = is_symbol(c);
                     if any_symbol {
                         break;
@@ -6,30 +6,26 @@ use aoc_runner_derive::{aoc, aoc_generator};
 
 use hashbrown::HashSet;
 
+use super::parse::{self, ParseError};
+
 type InputType = (HashSet<u16>, HashSet<u16>);
 type SolutionType = usize;
 
 #[aoc_generator(day4)]
-pub fn input_generator(input: &str) -> Vec<InputType> {
+pub fn input_generator(input: &str) -> Result<Vec<InputType>, ParseError> {
     // Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+    let numbers = |numbers: &str| -> Result<HashSet<u16>, ParseError> {
+        Ok(parse::numbers(input, numbers, &[' '])?
+            .into_iter()
+            .collect())
+    };
     input
         .lines()
-        .map(|line| line.split_once(": ").expect("No Card separator").1)
-        .map(|card| {
+        .map(|line| {
+            let card = parse::header(input, line)?.1;
             // 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-            let cards = card.split_once(" | ").expect("No cards separator");
-            (
-                cards
-                    .0
-                    .split_ascii_whitespace()
-                    .map(|num| num.parse().expect("Number"))
-                    .collect(),
-                cards
-                    .1
-                    .split_ascii_whitespace()
-                    .map(|num| num.parse().expect("Number"))
-                    .collect(),
-            )
+            let cards = parse::split_once(input, card, " | ")?;
+            Ok((numbers(cards.0)?, numbers(cards.1)?))
         })
         .collect()
 }
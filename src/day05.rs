@@ -6,6 +6,8 @@ use aoc_runner_derive::{aoc, aoc_generator};
 use hashbrown::HashSet;
 use std::ops::Range;
 
+use super::parse::{self, ParseError};
+
 type Seed = i64;
 
 pub struct MappingData {
@@ -18,53 +20,37 @@ type InputType = (Vec<Seed>, Vec<MappingData>);
 type SolutionType = Seed;
 
 #[aoc_generator(day5)]
-pub fn input_generator(input: &str) -> InputType {
-    // Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-    let mut lines = input.lines();
-
-    let seeds = lines
-        .next()
-        .expect("Seeds line")
-        .split_once("seeds: ")
-        .expect("Seeds")
-        .1
-        .split_ascii_whitespace()
-        .map(|num| num.parse().expect("Seed number"))
-        .collect();
+pub fn input_generator(input: &str) -> Result<InputType, ParseError> {
+    // seeds: 79 14 55 13
+    let mut sections = parse::sections(input);
 
-    lines.next().expect("empty line");
+    let seeds = parse::next_section(input, &mut sections, "the seeds")?;
+    let seeds = parse::header_values(input, seeds, "seeds")?;
+    let seeds = parse::numbers(input, seeds, &[' '])?;
 
     let mut mappings = vec![];
 
-    while let Some(mapping) = lines.next() {
-        let names = mapping.split_once(" map:").expect("map").0;
-        let mut names = names.split('-');
-        let from_kind = names.next().expect("from name").to_string();
-        names.next();
-        let to_kind = names.next().expect("to name").to_string();
-        let mut ranges = vec![];
-        for range in lines.by_ref() {
-            if range.is_empty() {
-                break;
-            }
-            let mut range = range.split_ascii_whitespace();
-            let from_range = range
-                .next()
-                .expect("from range")
-                .parse()
-                .expect("From range");
-            let to_range = range.next().expect("to range").parse().expect("To range");
-            let length = range.next().expect("length").parse().expect("Range length");
-            ranges.push((from_range, to_range, length));
-        }
+    for mapping in sections {
+        // seed-to-soil map:
+        // 50 98 2
+        let mut lines = mapping.lines();
+        let names = parse::next_line(input, &mut lines, "a map")?;
+        let names = parse::split_once(input, names, " map:")?.0;
+        let (from_kind, to_kind) = parse::split_once(input, names, "-to-")?;
+        let ranges = lines
+            .map(|range| {
+                let [from_range, to_range, length] = parse::tuple(input, range, ' ')?;
+                Ok((from_range, to_range, length))
+            })
+            .collect::<Result<_, _>>()?;
         mappings.push(MappingData {
-            from_kind,
-            to_kind,
+            from_kind: from_kind.to_string(),
+            to_kind: to_kind.to_string(),
             ranges,
         });
     }
 
-    (seeds, mappings)
+    Ok((seeds, mappings))
 }
 
 fn translate_number(num: Seed, kind: &str, final_kind: &str, data: &InputType) -> SolutionType {
@@ -208,9 +194,11 @@ pub fn solve_part2(data: &InputType) -> SolutionType {
 }
 
 #[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
 mod test {
     use super::translate_range_with_mapping;
     use super::translate_range_with_mappings;
+    use hashbrown::HashSet;
 
     #[test]
     fn translate_range_before() {
@@ -3,47 +3,88 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 
 use aoc_runner_derive::{aoc, aoc_generator};
+use num::BigInt;
 
-type InputType = (u64, u64);
-type SolutionType = u64;
+use super::math::quadratic;
+use super::parse::{self, ParseError};
+
+type Race = (u128, u128);
+/// The races and the single race you get when ignoring the spaces
+type InputType = (Vec<Race>, Race);
+type SolutionType = u128;
 
 #[aoc_generator(day6)]
-pub fn input_generator(input: &str) -> Vec<InputType> {
+pub fn input_generator(input: &str) -> Result<InputType, ParseError> {
     // Time:    7    15   30
-    let mut input = input.lines().map(|line| {
-        line.split_once(": ")
-            .expect("No header separator")
-            .1
-            .split_ascii_whitespace()
-            .map(|num| num.parse().expect("Number"))
-    });
-    let times = input.next().expect("Time line");
-    let distances = input.next().expect("Distance line");
-    times.zip(distances).collect()
+    let mut lines = input.lines();
+    let mut numbers = |key: &str| -> Result<(Vec<u128>, u128), ParseError> {
+        let line = parse::next_line(input, &mut lines, key)?;
+        let values = parse::header_values(input, line, key)?;
+        let kerned: String = values.chars().filter(|c| !c.is_whitespace()).collect();
+        let kerned = kerned
+            .parse()
+            .map_err(|_| ParseError::new(input, values, "a number when joined"))?;
+        Ok((parse::numbers(input, values, &[' '])?, kerned))
+    };
+    let (times, time) = numbers("Time")?;
+    let (distances, distance) = numbers("Distance")?;
+    if times.len() != distances.len() {
+        return Err(ParseError::new(
+            input,
+            input.trim_end(),
+            "as many distances as times",
+        ));
+    }
+    Ok((times.into_iter().zip(distances).collect(), (time, distance)))
+}
+
+/// The number of ways to hold the button to go further than distance
+///
+/// Holding for h ms moves the boat h * (time - h) mm, so the winning holds
+/// are where h² - time * h + distance < 0.
+fn ways_to_win((time, distance): Race) -> SolutionType {
+    // Widened to BigInt, as time² doesn't fit in u128
+    quadratic::negative_widened(1, -BigInt::from(time), distance).map_or(0, |holds| {
+        let ways: BigInt = holds.end() - holds.start() + 1;
+        ways.try_into().expect("Fewer ways than the time")
+    })
 }
 
 #[aoc(day6, part1)]
-pub fn solve_part1(data: &[InputType]) -> SolutionType {
-    data.iter()
-        .map(|&(time, dist)| {
-            let delta = time * time - 4 * dist;
-            let p1 = (time as f64 + f64::sqrt(delta as f64)) / 2.0;
-            let p2 = (time as f64 - f64::sqrt(delta as f64)) / 2.0;
-            let mut min = f64::ceil(p1.min(p2)) as u64;
-            let mut max = f64::floor(p1.max(p2)) as u64;
-            if min * (time - min) <= dist {
-                min += 1
-            }
-            if max * (time - max) <= dist {
-                max -= 1
-            }
-            1 + max - min
-        })
-        .product()
+pub fn solve_part1(data: &InputType) -> SolutionType {
+    data.0.iter().map(|&race| ways_to_win(race)).product()
 }
 
 #[aoc(day6, part2)]
-pub fn solve_part2(_data: &[InputType]) -> SolutionType {
-    // Solved with part 1 and modified input file...
-    0
+pub fn solve_part2(data: &InputType) -> SolutionType {
+    ways_to_win(data.1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ways_to_win_at_the_boundaries() {
+        assert_eq!(ways_to_win((7, 9)), 4);
+        assert_eq!(ways_to_win((30, 200)), 9);
+        assert_eq!(ways_to_win((71530, 940200)), 71503);
+        assert_eq!(ways_to_win((4, 4)), 0);
+        assert_eq!(ways_to_win((4, 3)), 1);
+        assert_eq!(ways_to_win((3, 100)), 0);
+        assert_eq!(ways_to_win((0, 0)), 0);
+        // Too large to be exact with f64, and time² overflows u128
+        let time = 1 << 64;
+        let distance = (time / 2 - 1) * (time / 2 + 1);
+        assert_eq!(ways_to_win((time, distance)), 1);
+        assert_eq!(ways_to_win((time, distance - 1)), 3);
+    }
+
+    #[test]
+    fn races_are_joined() {
+        let input = "Time:      7  15   30\nDistance:  9  40  200\n";
+        let (races, race) = input_generator(input).expect("Valid input");
+        assert_eq!(solve_part1(&(races.clone(), race)), 288);
+        assert_eq!(race, (71530, 940200));
+    }
 }
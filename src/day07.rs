@@ -9,15 +9,20 @@ type SolutionType = usize;
 
 use std::cmp::Ordering;
 
+use super::parse::{self, ParseError};
+
 #[aoc_generator(day7)]
-pub fn input_generator(input: &str) -> Vec<InputType> {
+pub fn input_generator(input: &str) -> Result<Vec<InputType>, ParseError> {
     // 32T3K 795
     input
         .lines()
         .map(|line| {
-            let line = line.split_once(' ').expect("No hand separator");
+            let line = parse::split_once(input, line, " ")?;
+            if line.0.len() != 5 {
+                return Err(ParseError::new(input, line.0, "five cards"));
+            }
             let mut hand = [0; 5];
-            for (i, c) in line.0.chars().enumerate() {
+            for (i, c) in line.0.char_indices() {
                 hand[i] = match c {
                     'A' => 14u8,
                     'K' => 13u8,
@@ -25,10 +30,13 @@ pub fn input_generator(input: &str) -> Vec<InputType> {
                     'J' => 11u8,
                     'T' => 10u8,
                     '1'..='9' => c.to_digit(10).expect("digit") as u8,
-                    _ => panic!("Unexpected char {}", c),
+                    _ => {
+                        let card = &line.0[i..i + c.len_utf8()];
+                        return Err(ParseError::new(input, card, "a card"));
+                    }
                 }
             }
-            (hand, line.1.parse().expect("bet"))
+            Ok((hand, parse::number(input, line.1)?))
         })
         .collect()
 }
@@ -4,34 +4,42 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 use hashbrown::HashMap;
+use itertools::Itertools;
+use num::Integer;
 use regex::Regex;
 
+use super::math::crt;
+use super::parse::{self, ParseError};
+
 type InputType = (String, HashMap<String, (String, String)>);
 type SolutionType = usize;
 
 #[aoc_generator(day8)]
-pub fn input_generator(input: &str) -> InputType {
+pub fn input_generator(input: &str) -> Result<InputType, ParseError> {
     // RL
     //
     // AAA = (BBB, CCC)
-    let mut input = input.lines();
-    let path = input.next().expect("Path").to_string();
-    input.next();
+    let mut lines = input.lines();
+    let path = parse::next_line(input, &mut lines, "the path")?;
+    if let Some(pos) = path.find(|c| c != 'L' && c != 'R') {
+        return Err(ParseError::new(input, &path[pos..], "L or R"));
+    }
+    lines.next();
 
-    let re = Regex::new(r"(...) = \((...), (...)\)").expect("Compilable regex");
+    let re = Regex::new(r"^(...) = \((...), (...)\)$").expect("Compilable regex");
 
-    let tree = input
+    let tree = lines
         .map(|s| {
             let caps = re
                 .captures(s)
-                .unwrap_or_else(|| panic!("Didn't match: {}", s));
-            (
+                .ok_or_else(|| ParseError::new(input, s, "AAA = (BBB, CCC)"))?;
+            Ok((
                 caps[1].to_string(),
                 (caps[2].to_string(), caps[3].to_string()),
-            )
+            ))
         })
-        .collect();
-    (path, tree)
+        .collect::<Result<_, _>>()?;
+    Ok((path.to_string(), tree))
 }
 
 #[aoc(day8, part1)]
@@ -39,113 +47,186 @@ pub fn solve_part1(data: &InputType) -> SolutionType {
     let mut pos = "AAA";
 
     let mut length = 0;
-    for c in data.0.chars().cycle() {
+    for &c in data.0.as_bytes().iter().cycle() {
         if pos == "ZZZ" {
             break;
         }
         length += 1;
-        pos = match c {
-            'L' => &data.1.get(pos).expect("Find next").0,
-            'R' => &data.1.get(pos).expect("Find next").1,
-            _ => panic!("Unknown direction"),
-        };
+        pos = step(data, pos, c);
     }
 
     length
 }
 
-/*
-fn find_cycle(pos: &str, data: &InputType) -> (u64, u64) {
-    println!("Solving for {}", pos);
-    let mut pos = pos;
-    let mut path: Vec<&str> = vec![pos];
-    for c in data.0.chars().cycle() {
-        pos = match c {
-            'L' => &data.1.get(pos).expect("Find next").0,
-            'R' => &data.1.get(pos).expect("Find next").1,
-            _ => panic!("Unknown direction"),
-        };
-        if path.iter().filter(|x| **x == pos).count() == 2 && pos.as_bytes()[2] == b'Z' {
-            let mut length = 0;
-            let mut offset = 0;
-            for old_pos in &path {
-                if old_pos == &pos {
-                    if offset == 0 {
-                        println!("Cycle started at {}", length);
-                        offset = length;
-                    } else {
-                        println!(
-                            "Cycle starts again at {}, diff={}, before offset={}, before={}",
-                            length,
-                            length - offset,
-                            path[offset as usize - 1],
-                            path[length as usize - 1]
-                        );
-                    }
-                } else if old_pos.as_bytes()[2] == b'Z' {
-                    println!("Goal at {}, diff={}", length, length - offset);
-                }
-                length += 1;
-            }
-            println!(
-                "Total length: {}, diff={}, before={}",
-                length,
-                length - offset,
-                path[length as usize - 1]
-            );
-            return (offset, length - offset);
-        }
-        path.push(pos);
+fn step<'a>(data: &'a InputType, pos: &str, c: u8) -> &'a str {
+    let next = data.1.get(pos).expect("Find next");
+    match c {
+        b'L' => &next.0,
+        b'R' => &next.1,
+        _ => unreachable!("The generator only accepts L and R"),
     }
-    panic!("Shouldn't reach here");
 }
-*/
 
-fn find_first_goal(pos: &str, data: &InputType) -> u64 {
-    // The periods, in the input, are always equal to the length from
-    // the start to the first goal, making it possible to skip finding
-    // the real loop and also makes it possible to use lcm on the results.
-    let mut pos = pos;
-    let mut length = 0;
-    for c in data.0.chars().cycle() {
-        pos = match c {
-            'L' => &data.1.get(pos).expect("Find next").0,
-            'R' => &data.1.get(pos).expect("Find next").1,
-            _ => panic!("Unknown direction"),
+fn is_goal(pos: &str) -> bool {
+    pos.ends_with('Z')
+}
+
+/// When a ghost is at a goal
+///
+/// The ghost's state is its node and its index in the path, which repeats
+/// after prefix steps with a period of length steps.
+struct GhostCycle {
+    prefix: u64,
+    length: u64,
+    /// The steps before prefix at a goal
+    prefix_goals: Vec<u64>,
+    /// The steps in prefix..prefix + length at a goal
+    cycle_goals: Vec<u64>,
+}
+
+impl GhostCycle {
+    fn find(pos: &str, data: &InputType) -> Self {
+        let path = data.0.as_bytes();
+        let mut seen = HashMap::new();
+        let mut goals = vec![];
+        let mut pos = pos;
+        let mut steps = 0;
+        let prefix = loop {
+            let idx = steps as usize % path.len();
+            if let Some(&prefix) = seen.get(&(pos, idx)) {
+                break prefix;
+            }
+            seen.insert((pos, idx), steps);
+            if is_goal(pos) {
+                goals.push(steps);
+            }
+            pos = step(data, pos, path[idx]);
+            steps += 1;
         };
-        length += 1;
-        if pos.as_bytes()[2] == b'Z' {
-            return length;
+        let cycle_start = goals.partition_point(|&goal| goal < prefix);
+        Self {
+            prefix,
+            length: steps - prefix,
+            cycle_goals: goals.split_off(cycle_start),
+            prefix_goals: goals,
+        }
+    }
+
+    fn is_goal(&self, steps: u64) -> bool {
+        if steps < self.prefix {
+            self.prefix_goals.contains(&steps)
+        } else {
+            let steps = self.prefix + (steps - self.prefix) % self.length;
+            self.cycle_goals.contains(&steps)
         }
     }
-    panic!("Shouldn't reach here");
 }
 
-fn gcd(a: u64, b: u64) -> u64 {
-    let mut max = a.max(b);
-    let mut min = a.min(b);
+/// The first step where all ghosts are at a goal, if they ever are
+fn synchronize(ghosts: &[GhostCycle]) -> Option<u64> {
+    // Before the longest prefix, only that ghost's prefix goals can work
+    let longest = ghosts.iter().max_by_key(|ghost| ghost.prefix)?;
+    if let Some(&steps) = longest
+        .prefix_goals
+        .iter()
+        .find(|&&steps| ghosts.iter().all(|ghost| ghost.is_goal(steps)))
+    {
+        return Some(steps);
+    }
 
-    loop {
-        let r = max % min;
-        if r == 0 {
-            return min;
-        }
-        max = min;
-        min = r;
+    // After it, every ghost is in its cycle. Each ghost's goals are
+    // combined with the steps that still work for the ghosts before it,
+    // which all share one period, so there are never more than that.
+    let mut residues = vec![(0, 1)];
+    for ghost in ghosts {
+        let length = i128::from(ghost.length);
+        residues = residues
+            .iter()
+            .cartesian_product(&ghost.cycle_goals)
+            .filter_map(|(&residue, &goal)| crt([residue, (i128::from(goal), length)]))
+            .collect();
+        residues.sort_unstable();
+        residues.dedup();
     }
+    let first = i128::from(longest.prefix);
+    residues
+        .into_iter()
+        .map(|(offset, period)| {
+            // The first solution that isn't before any ghost's cycle
+            let behind = (first - offset).max(0);
+            offset + period * Integer::div_ceil(&behind, &period)
+        })
+        .min()
+        .map(|steps| u64::try_from(steps).expect("Steps fit in u64"))
 }
 
-fn lcm(a: u64, b: u64) -> u64 {
-    a * (b / gcd(a, b))
+fn ghosts(data: &InputType) -> Vec<GhostCycle> {
+    data.1
+        .keys()
+        .filter(|s| s.ends_with('A'))
+        .map(|pos| GhostCycle::find(pos, data))
+        .collect()
 }
 
 #[aoc(day8, part2)]
 pub fn solve_part2(data: &InputType) -> u64 {
-    let positions: Vec<_> = data.1.keys().filter(|s| s.as_bytes()[2] == b'A').collect();
+    synchronize(&ghosts(data)).expect("The ghosts are never all at a goal")
+}
 
-    let results: Vec<_> = positions
-        .iter()
-        .map(|pos| find_first_goal(pos, data))
-        .collect();
-    results.iter().fold(1, |acc, &v| lcm(acc, v))
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn part2(input: &str) -> u64 {
+        solve_part2(&input_generator(input).expect("Valid input"))
+    }
+
+    #[test]
+    fn periods_different_from_the_first_goal() {
+        // 11A is at a goal after 2, 4, 6... steps and 22A after 1, 4, 7...
+        let input =
+            "LR\n\n11A = (11B, 11B)\n11B = (11Z, 11Z)\n11Z = (11C, 11C)\n11C = (11Z, 11Z)\n\
+                     22A = (22Z, 22Z)\n22Z = (22B, 22B)\n22B = (22C, 22C)\n22C = (22Z, 22Z)";
+        assert_eq!(part2(input), 4);
+    }
+
+    #[test]
+    fn goals_before_the_cycle() {
+        let input = "L\n\n11A = (11Z, 11Z)\n11Z = (11B, 11B)\n11B = (11B, 11B)\n\
+                     22A = (22Z, 22Z)\n22Z = (22Z, 22Z)";
+        assert_eq!(part2(input), 1);
+    }
+
+    #[test]
+    fn never_synchronizes() {
+        // 11A is at a goal after an even number of steps and 22A after an odd
+        let input = "L\n\n11A = (11B, 11B)\n11B = (11Z, 11Z)\n11Z = (11C, 11C)\n11C = (11Z, 11Z)\n\
+                     22A = (22B, 22B)\n22B = (22C, 22C)\n22C = (22Z, 22Z)\n22Z = (22C, 22C)";
+        let data = input_generator(input).expect("Valid input");
+        assert_eq!(synchronize(&ghosts(&data)), None);
+    }
+
+    #[test]
+    fn many_goals_per_cycle() {
+        // Nine ghosts on cycles of ten nodes, where ghost k misses the
+        // goal only after k + 1 steps, so they all meet after ten steps
+        let mut input = "L\n\n".to_string();
+        for (k, ghost) in ('B'..='J').enumerate() {
+            let name = |p: usize| match p % 10 {
+                p if p == k + 1 => format!("{ghost}{p}X"),
+                p => format!("{ghost}{p}Z"),
+            };
+            input += &format!("{ghost}0A = ({0}, {0})\n", name(1));
+            for p in 1..=10 {
+                input += &format!("{} = ({1}, {1})\n", name(p), name(p + 1));
+            }
+        }
+        assert_eq!(part2(input.trim_end()), 10);
+    }
+
+    #[test]
+    fn only_l_and_r() {
+        let error = input_generator("LRX\n\nAAA = (AAA, AAA)").expect_err("Not L or R");
+        assert_eq!((error.line, error.column), (1, 3));
+    }
 }
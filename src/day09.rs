@@ -4,18 +4,16 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use super::parse::{self, ParseError};
+
 type InputType = Vec<i64>;
 type SolutionType = i64;
 
 #[aoc_generator(day9)]
-pub fn input_generator(input: &str) -> Vec<InputType> {
+pub fn input_generator(input: &str) -> Result<Vec<InputType>, ParseError> {
     input
         .lines()
-        .map(|line| {
-            line.split_ascii_whitespace()
-                .map(|s| s.parse().expect("Number"))
-                .collect()
-        })
+        .map(|line| parse::numbers(input, line, &[' ']))
         .collect()
 }
 
@@ -4,58 +4,59 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use super::parse::{self, ParseError};
 use super::world::*;
 use hashbrown::HashSet;
 
 type SolutionType = i32;
 
 #[aoc_generator(day10)]
-pub fn input_generator(input: &str) -> Map {
-    Map::from_string(input)
+pub fn input_generator(input: &str) -> Result<Map, ParseError> {
+    parse::map(input)
 }
 
-fn is_up(c: u8) -> bool {
-    matches!(c, b'S' | b'|' | b'L' | b'J')
-}
-
-fn is_down(c: u8) -> bool {
-    matches!(c, b'S' | b'|' | b'F' | b'7')
-}
-
-fn is_left(c: u8) -> bool {
-    matches!(c, b'S' | b'-' | b'J' | b'7')
+/// The directions the pipe connects
+fn exits(c: u8) -> &'static [CardinalDir] {
+    use CardinalDir::*;
+    match c {
+        b'|' => &[North, South],
+        b'-' => &[East, West],
+        b'L' => &[North, East],
+        b'J' => &[North, West],
+        b'7' => &[South, West],
+        b'F' => &[South, East],
+        b'S' => &[North, East, South, West],
+        _ => &[],
+    }
 }
 
-fn is_right(c: u8) -> bool {
-    matches!(c, b'S' | b'-' | b'F' | b'L')
+/// The pipe hidden under S, found from the pipes that connect to it
+fn start_pipe(map: &Map, start: Point) -> u8 {
+    let connected: Vec<_> = CardinalDir::all()
+        .filter(|&dir| {
+            map.get_at(start.walk(dir))
+                .is_some_and(|c| exits(c).contains(&dir.opposite()))
+        })
+        .collect();
+    b"|-LJ7F"
+        .iter()
+        .copied()
+        .find(|&c| {
+            exits(c).len() == connected.len() && exits(c).iter().all(|dir| connected.contains(dir))
+        })
+        .expect("Two pipes connect to the start")
 }
 
 fn find_exits(map: &Map, from: Point) -> [Point; 2] {
     let mut result = [from; 2];
     let mut idx = 0;
-    let from_c = map.get_at_unchecked(from);
-    for (pos, dir, c) in map.neighbors(from) {
-        if match (dir, c) {
-            (Dir::North, b'|') => is_up(from_c),
-            (Dir::North, b'F') => is_up(from_c),
-            (Dir::North, b'7') => is_up(from_c),
-            (Dir::North, b'S') => is_up(from_c),
-            (Dir::South, b'|') => is_down(from_c),
-            (Dir::South, b'L') => is_down(from_c),
-            (Dir::South, b'J') => is_down(from_c),
-            (Dir::South, b'S') => is_down(from_c),
-            (Dir::West, b'-') => is_left(from_c),
-            (Dir::West, b'L') => is_left(from_c),
-            (Dir::West, b'F') => is_left(from_c),
-            (Dir::West, b'S') => is_left(from_c),
-            (Dir::East, b'-') => is_right(from_c),
-            (Dir::East, b'J') => is_right(from_c),
-            (Dir::East, b'7') => is_right(from_c),
-            (Dir::East, b'S') => is_right(from_c),
-            _ => false,
-        } {
-            result[idx] = pos;
-            idx += 1;
+    for &dir in exits(map.get_at_unchecked(from)) {
+        let pos = from.walk(dir);
+        if let Some(c) = map.get_at(pos) {
+            if exits(c).contains(&dir.opposite()) {
+                result[idx] = pos;
+                idx += 1;
+            }
         }
     }
     for pos in result {
@@ -126,10 +127,13 @@ pub fn solve_part2(map: &Map) -> SolutionType {
         }
     }
 
+    let start_pipe = start_pipe(map, start);
+    let start_pos = start;
     let mut inside = false;
     let mut start = b'.';
     i32::try_from(
         map.iter()
+            .map(|(pos, c)| (pos, if pos == start_pos { start_pipe } else { c }))
             .filter(|(pos, c)| {
                 if pos.x == 0 {
                     inside = false;
@@ -160,3 +164,71 @@ pub fn solve_part2(map: &Map) -> SolutionType {
     )
     .expect("count within range")
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn part1_example() {
+        let map = input_generator("7-F7-\n.FJ|7\nSJLL7\n|F--J\nLJ.LJ\n").expect("Valid input");
+        assert_eq!(solve_part1(&map), 8);
+    }
+
+    #[test]
+    fn part2_examples() {
+        // S is an F here, so it starts the row's first corner pair
+        let input = "...........
+.S-------7.
+.|F-----7|.
+.||.....||.
+.||.....||.
+.|L-7.F-J|.
+.|..|.|..|.
+.L--J.L--J.
+...........
+";
+        let map = input_generator(input).expect("Valid input");
+        assert_eq!(solve_part2(&map), 4);
+
+        let input = ".F----7F7F7F7F-7....
+.|F--7||||||||FJ....
+.||.FJ||||||||L7....
+FJL7L7LJLJ||LJ.L-7..
+L--J.L7...LJS7F-7L7.
+....F-J..F7FJ|L7L7L7
+....L7.F7||L7|.L7L7|
+.....|FJLJ|FJ|F7|.LJ
+....FJL-7.||.||||...
+....L---J.LJ.LJLJ...
+";
+        let map = input_generator(input).expect("Valid input");
+        assert_eq!(solve_part2(&map), 8);
+
+        let input = "FF7FSF7F7F7F7F7F---7
+L|LJ||||||||||||F--J
+FL-7LJLJ||||||LJL-77
+F--JF--7||LJLJ7F7FJ-
+L---JF-JLJ.||-FJLJJ7
+|F|F-JF---7F7-L7L|7|
+|FFJF7L7F-JF7|JL---7
+7-L-JL7||F7|L7F-7F7|
+L.L7LFJ|||||FJL7||LJ
+L7JLJL-JLJLJL--JLJ.L
+";
+        let map = input_generator(input).expect("Valid input");
+        assert_eq!(solve_part2(&map), 10);
+    }
+
+    #[test]
+    fn start_pipes() {
+        let map = input_generator(".....\n.S-7.\n.|.|.\n.L-J.\n.....\n").expect("Valid input");
+        assert_eq!(start_pipe(&map, Point { x: 1, y: 1 }), b'F');
+        let map = input_generator("-L|F7\n7S-7|\nL|7||\n-L-J|\nL|-JF\n").expect("Valid input");
+        assert_eq!(start_pipe(&map, Point { x: 1, y: 1 }), b'F');
+        let map = input_generator("..F7.\n.FJ|.\nSJ.L7\n|F--J\nLJ...\n").expect("Valid input");
+        assert_eq!(start_pipe(&map, Point { x: 0, y: 2 }), b'F');
+        let map = input_generator("F-S\n|.|\nL-J\n").expect("Valid input");
+        assert_eq!(start_pipe(&map, Point { x: 2, y: 0 }), b'7');
+    }
+}
@@ -2,14 +2,21 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
-use aoc_runner_derive::aoc;
+use aoc_runner_derive::{aoc, aoc_generator};
 
+use super::parse::{self, ParseError};
 use super::world::*;
 use hashbrown::HashSet;
 
+type SolutionType = i64;
+
+#[aoc_generator(day11)]
+pub fn input_generator(input: &str) -> Result<Map<i64>, ParseError> {
+    parse::map(input)
+}
+
 #[aoc(day11, part1)]
-pub fn solve_part1(input: &str) -> i32 {
-    let map = Map::<i32>::from_string(input);
+pub fn solve_part1(map: &Map<i64>) -> SolutionType {
     let mut galaxies = map.find(b'#');
 
     let rows: HashSet<_> = galaxies.iter().map(|p| p.y).collect();
@@ -43,8 +50,7 @@ pub fn solve_part1(input: &str) -> i32 {
 }
 
 #[aoc(day11, part2)]
-pub fn solve_part2(input: &str) -> i64 {
-    let map = Map::<i64>::from_string(input);
+pub fn solve_part2(map: &Map<i64>) -> SolutionType {
     let mut galaxies: Vec<_> = map.find(b'#');
 
     let rows: HashSet<_> = galaxies.iter().map(|p| p.y).collect();
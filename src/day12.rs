@@ -7,23 +7,24 @@ use aoc_runner_derive::{aoc, aoc_generator};
 use hashbrown::HashMap;
 use rayon::prelude::*;
 
+use super::parse::{self, ParseError};
+
 type InputType = (Vec<u8>, Vec<u8>);
 type SolutionType = usize;
 
 #[aoc_generator(day12)]
-pub fn input_generator(input: &str) -> Vec<InputType> {
+pub fn input_generator(input: &str) -> Result<Vec<InputType>, ParseError> {
     // .??..??...?##. 1,1,3
     input
         .lines()
         .map(|line| {
-            let line = line.split_once(' ').expect("No game separator");
+            let line = parse::split_once(input, line, " ")?;
+            if let Some(pos) = line.0.find(|c| !matches!(c, '.' | '#' | '?')) {
+                return Err(ParseError::new(input, &line.0[pos..], "., # or ?"));
+            }
             let springs = Vec::from(line.0.as_bytes());
-            let groups = line
-                .1
-                .split(',')
-                .map(|n| n.parse().expect("number"))
-                .collect();
-            (springs, groups)
+            let groups = parse::numbers(input, line.1, &[','])?;
+            Ok((springs, groups))
         })
         .collect()
 }
@@ -4,14 +4,15 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use super::parse::{self, ParseError};
 use super::world::*;
 // use rayon::prelude::*;
 
-type InputType = String;
+type InputType = Map<i32>;
 type SolutionType = usize;
 
 #[aoc_generator(day13)]
-pub fn input_generator(input: &str) -> Vec<InputType> {
+pub fn input_generator(input: &str) -> Result<Vec<InputType>, ParseError> {
     // #.#
     // ...
     // ...
@@ -20,13 +21,17 @@ pub fn input_generator(input: &str) -> Vec<InputType> {
     // ...
     // #..
     // Max 17x17 characters
-    input.split("\n\n").map(|s| s.to_string()).collect()
+    parse::sections(input)
+        .map(|pattern| {
+            Map::try_from_str(pattern)
+                .map_err(|error| ParseError::from_map_error(input, pattern, error))
+        })
+        .collect()
 }
 
 #[aoc(day13, part1)]
 pub fn solve_part1(data: &[InputType]) -> SolutionType {
     data.iter()
-        .map(|s| Map::<i32>::from_string(s))
         .map(|map| {
             let width = map.get_width();
             let height = map.get_height();
@@ -34,10 +39,7 @@ pub fn solve_part1(data: &[InputType]) -> SolutionType {
                 for y in 0..height {
                     for x in 0..=i {
                         let from = Point { x, y };
-                        let to = Point {
-                            x: 2 * (i + 1) - x - 1,
-                            y,
-                        };
+                        let to = from.mirror_x(i + 1);
                         if to.x > i
                             && to.x < width
                             && map.get_at_unchecked(from) != map.get_at_unchecked(to)
@@ -53,10 +55,7 @@ pub fn solve_part1(data: &[InputType]) -> SolutionType {
                 for y in 0..=i {
                     for x in 0..width {
                         let from = Point { x, y };
-                        let to = Point {
-                            y: 2 * (i + 1) - y - 1,
-                            x,
-                        };
+                        let to = from.mirror_y(i + 1);
                         if to.y > i
                             && to.y < height
                             && map.get_at_unchecked(from) != map.get_at_unchecked(to)
@@ -78,7 +77,6 @@ pub fn solve_part1(data: &[InputType]) -> SolutionType {
 #[aoc(day13, part2)]
 pub fn solve_part2(data: &[InputType]) -> SolutionType {
     data.iter()
-        .map(|s| Map::<i32>::from_string(s))
         .map(|map| {
             let width = map.get_width();
             let height = map.get_height();
@@ -88,10 +86,7 @@ pub fn solve_part2(data: &[InputType]) -> SolutionType {
                 for y in 0..height {
                     for x in 0..=i {
                         let from = Point { x, y };
-                        let to = Point {
-                            x: 2 * (i + 1) - x - 1,
-                            y,
-                        };
+                        let to = from.mirror_x(i + 1);
                         if to.x > i
                             && to.x < width
                             && map.get_at_unchecked(from) != map.get_at_unchecked(to)
@@ -115,10 +110,7 @@ pub fn solve_part2(data: &[InputType]) -> SolutionType {
                 for y in 0..=i {
                     for x in 0..width {
                         let from = Point { x, y };
-                        let to = Point {
-                            y: 2 * (i + 1) - y - 1,
-                            x,
-                        };
+                        let to = from.mirror_y(i + 1);
                         if to.y > i
                             && to.y < height
                             && map.get_at_unchecked(from) != map.get_at_unchecked(to)
@@ -4,33 +4,23 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use super::cycle;
+use super::parse::{self, ParseError};
 use super::world::*;
-use hashbrown::*;
 // use rayon::prelude::*;
 
 type SolutionType = usize;
 
 #[aoc_generator(day14)]
-pub fn input_generator(input: &str) -> Map {
-    Map::from_string_with_border(input)
+pub fn input_generator(input: &str) -> Result<Map, ParseError> {
+    parse::map_with_border(input)
 }
 
 fn tilt(map: &mut Map, from: Dir, to: Dir) {
-    let slide_o = |map: &Map, pos: Point, c| {
-        match c {
-            b'.' => {
-                if map.get_at_unchecked(pos.walk(from)) == b'O' {
-                    return b'O';
-                }
-            }
-            b'O' => {
-                if map.get_at_unchecked(pos.walk(to)) == b'.' {
-                    return b'.';
-                }
-            }
-            _ => (),
-        }
-        c
+    let slide_o = |map: &Map, pos: Point, c| match c {
+        b'.' if map.get_at_unchecked(pos.walk(from)) == b'O' => b'O',
+        b'O' if map.get_at_unchecked(pos.walk(to)) == b'.' => b'.',
+        _ => c,
     };
     while map.transform(slide_o) {}
 }
@@ -52,29 +42,58 @@ pub fn solve_part1(map: &Map) -> SolutionType {
     total_value(&map)
 }
 
+/// Tilts the map north, west, south and then east
+fn spin(map: &Map) -> Map {
+    let mut map = map.clone();
+    tilt(&mut map, Dir::South, Dir::North);
+    tilt(&mut map, Dir::East, Dir::West);
+    tilt(&mut map, Dir::North, Dir::South);
+    tilt(&mut map, Dir::West, Dir::East);
+    map
+}
+
 #[aoc(day14, part2)]
 pub fn solve_part2(map: &Map) -> SolutionType {
-    let mut map = map.clone();
-    let mut prev = HashMap::new();
-    for cycle in 1..1_000 {
-        tilt(&mut map, Dir::South, Dir::North);
-        tilt(&mut map, Dir::East, Dir::West);
-        tilt(&mut map, Dir::North, Dir::South);
-        tilt(&mut map, Dir::West, Dir::East);
-        if prev.contains_key(&map) {
-            let start = prev.get(&map).unwrap();
-            let end = 1_000_000_000;
-            let loop_length = cycle - start;
-            let target = start + (end - start) % loop_length;
-            for (m, g) in prev.iter() {
-                if *g == target {
-                    return total_value(m);
-                }
-            }
-            break;
-        } else {
-            prev.insert(map.clone(), cycle);
+    // Whole maps are compared, as a hash collision would give a wrong answer.
+    // nth spins again, but at most start + length times, which is no more
+    // than finding the cycle took.
+    let spins = cycle::find_hashed(map, spin);
+    total_value(&spins.nth(map, spin, 1_000_000_000))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....
+";
+
+    #[test]
+    fn example() {
+        let map = input_generator(EXAMPLE).expect("Valid input");
+        assert_eq!(solve_part1(&map), 136);
+        assert_eq!(solve_part2(&map), 64);
+    }
+
+    #[test]
+    fn spin_cycle() {
+        let map = input_generator(EXAMPLE).expect("Valid input");
+        let spins = cycle::find_hashed(&map, spin);
+        assert_eq!(spins, cycle::brent(&map, spin));
+        let n = spins.start + 3 * spins.length + 2;
+        let mut spun = map.clone();
+        for _ in 0..n {
+            spun = spin(&spun);
         }
+        assert_eq!(spins.nth(&map, spin, n), spun);
     }
-    0
 }
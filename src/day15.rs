@@ -4,13 +4,24 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use super::parse::ParseError;
+
 type InputType = String;
 type SolutionType = usize;
 
 #[aoc_generator(day15)]
-pub fn input_generator(input: &str) -> Vec<InputType> {
+pub fn input_generator(input: &str) -> Result<Vec<InputType>, ParseError> {
     // rn=1,cm-,qp=3
-    input.split(',').map(|s| s.to_string()).collect()
+    input
+        .trim_end()
+        .split(',')
+        .map(|step| {
+            if step.is_empty() || !step.bytes().all(|c| c.is_ascii_graphic()) {
+                return Err(ParseError::new(input, step, "a step"));
+            }
+            Ok(step.to_string())
+        })
+        .collect()
 }
 
 fn get_hash<S: AsRef<str>>(s: S) -> u8 {
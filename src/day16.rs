@@ -4,31 +4,77 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use super::parse::{self, ParseError};
 use super::world::*;
 use hashbrown::HashSet;
 use rayon::prelude::*;
 
 type SolutionType = usize;
 
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Tile {
+    Empty,
+    /// `/`
+    Mirror,
+    /// `\`
+    BackMirror,
+    /// `-`
+    HorizontalSplitter,
+    /// `|`
+    VerticalSplitter,
+}
+
+impl TryFrom<u8> for Tile {
+    type Error = u8;
+
+    fn try_from(c: u8) -> Result<Self, Self::Error> {
+        use Tile::*;
+        match c {
+            b'.' => Ok(Empty),
+            b'/' => Ok(Mirror),
+            b'\\' => Ok(BackMirror),
+            b'-' => Ok(HorizontalSplitter),
+            b'|' => Ok(VerticalSplitter),
+            _ => Err(c),
+        }
+    }
+}
+
+impl From<Tile> for u8 {
+    fn from(tile: Tile) -> u8 {
+        use Tile::*;
+        match tile {
+            Empty => b'.',
+            Mirror => b'/',
+            BackMirror => b'\\',
+            HorizontalSplitter => b'-',
+            VerticalSplitter => b'|',
+        }
+    }
+}
+
+type Contraption = Map<i32, Tile>;
+
 #[aoc_generator(day16)]
-pub fn input_generator(input: &str) -> Map {
-    Map::from_string(input)
+pub fn input_generator(input: &str) -> Result<Contraption, ParseError> {
+    parse::map(input)
 }
 
 fn add_light(
-    moving_lights: &mut HashSet<(Point, Dir)>,
-    to_expand: &mut Vec<(Point, Dir)>,
-    map: &Map,
+    moving_lights: &mut HashSet<(Point, CardinalDir)>,
+    to_expand: &mut Vec<(Point, CardinalDir)>,
+    map: &Contraption,
     pos: Point,
-    dir: Dir,
+    dir: CardinalDir,
 ) {
     if map.is_inside_map(pos) && moving_lights.insert((pos, dir)) {
         to_expand.push((pos, dir));
     }
 }
 
-fn calculate_energize(map: &Map, start: Point, dir: Dir) -> SolutionType {
-    use Dir::*;
+fn calculate_energize(map: &Contraption, start: Point, dir: CardinalDir) -> SolutionType {
+    use CardinalDir::*;
+    use Tile::*;
 
     let mut moving_lights = HashSet::new();
     let mut to_expand = vec![];
@@ -39,27 +85,27 @@ fn calculate_energize(map: &Map, start: Point, dir: Dir) -> SolutionType {
     while let Some((mut pos, mut dir)) = to_expand.pop() {
         loop {
             energized.insert(pos);
-            let c = map.get_at_unchecked(pos);
-            match (c, dir) {
-                (b'-', East | West) | (b'|', North | South) | (b'.', _) => {
+            match (map.get_at_unchecked(pos), dir) {
+                (HorizontalSplitter, East | West)
+                | (VerticalSplitter, North | South)
+                | (Empty, _) => {
                     pos = pos.walk(dir);
                 }
-                (b'/', East | West) | (b'\\', North | South) => {
-                    dir = dir.turn_cardinal_left();
+                (Mirror, East | West) | (BackMirror, North | South) => {
+                    dir = dir.turn_left();
                     pos = pos.walk(dir);
                 }
-                (b'\\', East | West) | (b'/', North | South) => {
-                    dir = dir.turn_cardinal_right();
+                (BackMirror, East | West) | (Mirror, North | South) => {
+                    dir = dir.turn_right();
                     pos = pos.walk(dir);
                 }
-                (b'-', North | South) | (b'|', East | West) => {
-                    let dir1 = dir.turn_cardinal_left();
+                (HorizontalSplitter, North | South) | (VerticalSplitter, East | West) => {
+                    let dir1 = dir.turn_left();
                     let pos1 = pos.walk(dir1);
                     add_light(&mut moving_lights, &mut to_expand, map, pos1, dir1);
-                    dir = dir.turn_cardinal_right();
+                    dir = dir.turn_right();
                     pos = pos.walk(dir);
                 }
-                x => unreachable!("{:?}", x),
             }
             if map.is_inside_map(pos) && moving_lights.insert((pos, dir)) {
                 // The ray is still relevant
@@ -72,23 +118,23 @@ fn calculate_energize(map: &Map, start: Point, dir: Dir) -> SolutionType {
 }
 
 #[aoc(day16, part1)]
-pub fn solve_part1(map: &Map) -> SolutionType {
-    calculate_energize(map, Point { x: 0, y: 0 }, Dir::East)
+pub fn solve_part1(map: &Contraption) -> SolutionType {
+    calculate_energize(map, Point { x: 0, y: 0 }, CardinalDir::East)
 }
 
 #[aoc(day16, part2)]
-pub fn solve_part2(map: &Map) -> SolutionType {
+pub fn solve_part2(map: &Contraption) -> SolutionType {
     let e1 = (0..map.get_width())
         .into_par_iter()
         .map(|x| {
-            let e1 = calculate_energize(map, Point { x, y: 0 }, Dir::South);
+            let e1 = calculate_energize(map, Point { x, y: 0 }, CardinalDir::South);
             let e2 = calculate_energize(
                 map,
                 Point {
                     x,
                     y: map.get_height() - 1,
                 },
-                Dir::North,
+                CardinalDir::North,
             );
             e1.max(e2)
         })
@@ -97,14 +143,14 @@ pub fn solve_part2(map: &Map) -> SolutionType {
     let e2 = (0..map.get_height())
         .into_par_iter()
         .map(|y| {
-            let e1 = calculate_energize(map, Point { x: 0, y }, Dir::East);
+            let e1 = calculate_energize(map, Point { x: 0, y }, CardinalDir::East);
             let e2 = calculate_energize(
                 map,
                 Point {
                     x: map.get_width() - 1,
                     y,
                 },
-                Dir::West,
+                CardinalDir::West,
             );
             e1.max(e2)
         })
@@ -112,3 +158,27 @@ pub fn solve_part2(map: &Map) -> SolutionType {
         .expect("Number");
     e1.max(e2)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = r".|...\....
+|.-.\.....
+.....|-...
+........|.
+..........
+.........\
+..../.\\..
+.-.-/..|..
+.|....-|.\
+..//.|....
+";
+
+    #[test]
+    fn example() {
+        let map = input_generator(EXAMPLE).expect("Valid input");
+        assert_eq!(solve_part1(&map), 46);
+        assert_eq!(solve_part2(&map), 51);
+    }
+}
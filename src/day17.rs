@@ -4,162 +4,162 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use super::parse::{self, ParseError};
+use super::world::search::SearchProblem;
 use super::world::*;
 
 type SolutionType = u16;
 
-#[aoc_generator(day17)]
-pub fn input_generator(input: &str) -> Map {
-    Map::from_string(input)
-}
+/// The heat loss of a city block
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Heat(u8);
 
-#[derive(Debug, Clone, Eq, PartialEq)]
-struct CostAndPoint<T: Eq + PartialEq>(SolutionType, T);
+impl TryFrom<u8> for Heat {
+    type Error = u8;
 
-impl<T: Eq + PartialEq> Ord for CostAndPoint<T> {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.0.cmp(&self.0)
+    fn try_from(c: u8) -> Result<Self, Self::Error> {
+        if c.is_ascii_digit() {
+            Ok(Heat(c - b'0'))
+        } else {
+            Err(c)
+        }
     }
 }
 
-impl<T: Eq + PartialEq> PartialOrd for CostAndPoint<T> {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(other.0.cmp(&self.0))
+impl From<Heat> for u8 {
+    fn from(heat: Heat) -> u8 {
+        heat.0 + b'0'
     }
 }
 
-type PointData = (u8, Point, Dir);
-
-fn bfs(map: &Map, from: Point, to: Point) -> SolutionType {
-    let mut expanded = hashbrown::HashMap::new();
-    let mut to_expand = std::collections::BinaryHeap::new();
-    to_expand.push(CostAndPoint(0, (1, from.walk(Dir::East), Dir::East)));
-    to_expand.push(CostAndPoint(0, (1, from.walk(Dir::South), Dir::South)));
-    while let Some(CostAndPoint(mut heat_loss, (steps, pos, dir))) = to_expand.pop() {
-        if let Some(cost) = expanded.get_mut(&(steps, pos, dir)) {
-            if *cost <= heat_loss {
-                continue;
-            }
-            *cost = heat_loss;
-        } else {
-            expanded.insert((steps, pos, dir), heat_loss);
-        }
-        heat_loss += SolutionType::from(map.get_at_unchecked(pos) - b'0');
-        if to == pos {
-            // println!("{} ", expanded.len());
-            return heat_loss;
-        }
-        let dir_left = dir.turn_cardinal_left();
-        let pos_left = pos.walk(dir_left);
-        if map.is_inside_map(pos_left) {
-            to_expand.push(CostAndPoint(heat_loss, (1, pos_left, dir_left)));
-        }
-        let dir_right = dir.turn_cardinal_right();
-        let pos_right = pos.walk(dir_right);
-        if map.is_inside_map(pos_right) {
-            to_expand.push(CostAndPoint(heat_loss, (1, pos_right, dir_right)));
-        }
-        if steps < 3 {
-            let pos = pos.walk(dir);
-            if map.is_inside_map(pos) {
-                to_expand.push(CostAndPoint(heat_loss, (steps + 1, pos, dir)));
-            }
-        }
-    }
-    0
-}
+type HeatMap = Map<i32, Heat>;
 
-#[aoc(day17, part1)]
-pub fn solve_part1(map: &Map) -> SolutionType {
-    let goal = Point {
-        x: map.get_width() - 1,
-        y: map.get_height() - 1,
-    };
-    bfs(map, Point { x: 0, y: 0 }, goal)
+#[aoc_generator(day17)]
+pub fn input_generator(input: &str) -> Result<HeatMap, ParseError> {
+    parse::map(input)
 }
 
-// Turn into trait?
-fn search_for_node(map: &Map, start: &[PointData], goal: Point) -> SolutionType {
-    let mut expanded = hashbrown::HashMap::new();
-    let mut to_expand = std::collections::BinaryHeap::new();
+type PointData = (u8, Point, CardinalDir);
 
-    for node in start {
-        to_expand.push(CostAndPoint(0, (0, *node)));
-    }
+struct Crucible<'a> {
+    map: &'a HeatMap,
+    min_steps: u8,
+    max_steps: u8,
+}
 
-    while let Some(CostAndPoint(_estimated_cost, (mut ack_cost, node_state))) = to_expand.pop() {
-        if let Some(cost) = expanded.get_mut(&node_state) {
-            if *cost <= ack_cost {
-                continue;
-            }
-            *cost = ack_cost;
-        } else {
-            expanded.insert(node_state, ack_cost);
+impl<'a> Crucible<'a> {
+    fn goal(&self) -> Point {
+        Point {
+            x: self.map.get_width() - 1,
+            y: self.map.get_height() - 1,
         }
-        ack_cost += node_cost(map, &node_state);
-        let estimated_cost = ack_cost + node_underestimate_cost_to_goal(&node_state, goal);
-        if node_is_at_goal(goal, &node_state) {
-            // println!("{} ", expanded.len());
-            return ack_cost;
+    }
+
+    fn push_if_inside(
+        &self,
+        node_state: PointData,
+        successors: &mut Vec<(PointData, SolutionType)>,
+    ) {
+        let pos = node_state.1;
+        if self.map.is_inside_map(pos) {
+            let cost = SolutionType::from(self.map.get_at_unchecked(pos).0);
+            successors.push((node_state, cost));
         }
-        node_expand(&node_state, map, &mut to_expand, estimated_cost, ack_cost);
     }
-    0
 }
 
-fn node_is_at_goal(goal: Point, node_state: &PointData) -> bool {
-    goal == node_state.1
-}
+impl<'a> SearchProblem for Crucible<'a> {
+    type State = PointData;
+    type Cost = SolutionType;
 
-fn node_expand(
-    node_state: &PointData,
-    map: &Map,
-    to_expand: &mut std::collections::BinaryHeap<CostAndPoint<(u16, PointData)>>,
-    estimated_cost: u16,
-    ack_cost: u16,
-) {
-    let &(steps, pos, dir) = node_state;
-    if steps >= 4 {
-        let dir_left = dir.turn_cardinal_left();
-        let pos_left = pos.walk(dir_left);
-        if map.is_inside_map(pos_left) {
-            let new_state = (1, pos_left, dir_left);
-            to_expand.push(CostAndPoint(estimated_cost, (ack_cost, new_state)));
+    fn start_states(&self) -> Vec<Self::State> {
+        let start = Point { x: 0, y: 0 };
+        vec![
+            (0, start, CardinalDir::East),
+            (0, start, CardinalDir::South),
+        ]
+    }
+
+    fn successors(&self, node_state: &PointData, successors: &mut Vec<(PointData, SolutionType)>) {
+        let &(steps, pos, dir) = node_state;
+        if steps >= self.min_steps {
+            let dir_left = dir.turn_left();
+            self.push_if_inside((1, pos.walk(dir_left), dir_left), successors);
+            let dir_right = dir.turn_right();
+            self.push_if_inside((1, pos.walk(dir_right), dir_right), successors);
         }
-        let dir_right = dir.turn_cardinal_right();
-        let pos_right = pos.walk(dir_right);
-        if map.is_inside_map(pos_right) {
-            let new_state = (1, pos_right, dir_right);
-            to_expand.push(CostAndPoint(estimated_cost, (ack_cost, new_state)));
+        if steps < self.max_steps {
+            self.push_if_inside((steps + 1, pos.walk(dir), dir), successors);
         }
     }
-    if steps < 10 {
-        let pos = pos.walk(dir);
-        if map.is_inside_map(pos) {
-            let new_state = (steps + 1, pos, dir);
-            to_expand.push(CostAndPoint(estimated_cost, (ack_cost, new_state)));
-        }
+
+    fn is_goal(&self, node_state: &PointData) -> bool {
+        // The crucible also needs min_steps to stop at the goal
+        node_state.0 >= self.min_steps && self.goal() == node_state.1
     }
-}
 
-fn node_cost(map: &Map, node_state: &PointData) -> u16 {
-    SolutionType::from(map.get_at_unchecked(node_state.1) - b'0')
+    fn heuristic(&self, node_state: &PointData) -> SolutionType {
+        node_state.1.manhattan_distance(self.goal()) as SolutionType
+    }
 }
 
-fn node_underestimate_cost_to_goal(node_state: &PointData, goal: Point) -> u16 {
-    node_state.1.manhattan_distance(goal) as u16
+#[aoc(day17, part1)]
+pub fn solve_part1(map: &HeatMap) -> SolutionType {
+    let crucible = Crucible {
+        map,
+        min_steps: 0,
+        max_steps: 3,
+    };
+    search::search(&crucible).expect("Path to goal").0
 }
 
 #[aoc(day17, part2)]
-pub fn solve_part2(map: &Map) -> SolutionType {
-    let start = Point { x: 0, y: 0 };
-    let start = [
-        (1, start.walk(Dir::East), Dir::East),
-        (1, start.walk(Dir::South), Dir::South),
-    ];
-    let goal = Point {
-        x: map.get_width() - 1,
-        y: map.get_height() - 1,
+pub fn solve_part2(map: &HeatMap) -> SolutionType {
+    let crucible = Crucible {
+        map,
+        min_steps: 4,
+        max_steps: 10,
     };
-    search_for_node(map, &start, goal)
+    search::search(&crucible).expect("Path to goal").0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533
+";
+
+    #[test]
+    fn example() {
+        let map = input_generator(EXAMPLE).expect("Valid input");
+        assert_eq!(solve_part1(&map), 102);
+        assert_eq!(solve_part2(&map), 94);
+    }
+
+    #[test]
+    fn ultra_crucible_must_roll_four_blocks_to_stop() {
+        let map = input_generator(
+            "111111111111
+999999999991
+999999999991
+999999999991
+999999999991
+",
+        )
+        .expect("Valid input");
+        assert_eq!(solve_part2(&map), 71);
+    }
 }
@@ -2,17 +2,18 @@
 //
 // SPDX-License-Identifier: GPL-3.0-or-later
 
+use super::parse::{self, ParseError};
 use super::world::*;
 use aoc_runner_derive::{aoc, aoc_generator};
-use hashbrown::HashSet;
 use itertools::*;
 use regex::Regex;
 
-type InputType = (u8, u8, u32);
+/// The plan of part 1 and the one hidden in the color for part 2
+type InputType = (CardinalDir, u8, (CardinalDir, u32));
 type SolutionType = u64;
 
 #[aoc_generator(day18)]
-pub fn input_generator(input: &str) -> Vec<InputType> {
+pub fn input_generator(input: &str) -> Result<Vec<InputType>, ParseError> {
     // R 4 (#4b18e0)
 
     let re = Regex::new(r"^(.) ([0-9]*) \(#(.*)\)$").expect("Compilable regex");
@@ -22,12 +23,28 @@ pub fn input_generator(input: &str) -> Vec<InputType> {
         .map(|s| {
             let caps = re
                 .captures(s)
-                .unwrap_or_else(|| panic!("Didn't match: {}", s));
-            (
-                caps[1].as_bytes()[0],
-                caps[2].parse().expect("number"),
-                u32::from_str_radix(&caps[3], 16).expect("hex"),
-            )
+                .ok_or_else(|| ParseError::new(input, s, "R 4 (#4b18e0)"))?;
+            let group = |i| caps.get(i).expect("Matched group").as_str();
+            let dir = group(1)
+                .parse()
+                .map_err(|_| ParseError::new(input, group(1), "U, D, L or R"))?;
+            let color = group(3);
+            let num = u32::from_str_radix(color, 16)
+                .map_err(|_| ParseError::new(input, color, "a hex color"))?;
+            let hex_dir = match num & 15 {
+                0 => CardinalDir::East,
+                1 => CardinalDir::South,
+                2 => CardinalDir::West,
+                3 => CardinalDir::North,
+                _ => {
+                    return Err(ParseError::new(
+                        input,
+                        &color[color.len() - 1..],
+                        "a direction digit 0 to 3",
+                    ))
+                }
+            };
+            Ok((dir, parse::number(input, group(2))?, (hex_dir, num >> 4)))
         })
         .collect()
 }
@@ -36,47 +53,32 @@ pub fn input_generator(input: &str) -> Vec<InputType> {
 pub fn solve_part1(data: &[InputType]) -> SolutionType {
     let mut pos = Point { x: 0, y: 0 };
 
-    let mut points = HashSet::new();
-    points.insert(pos);
+    let mut trench = SparseMap::new();
+    trench.set_at(pos, b'#');
 
     for (dir, len, _) in data {
-        let dir = match dir {
-            b'U' => Dir::North,
-            b'D' => Dir::South,
-            b'L' => Dir::West,
-            b'R' => Dir::East,
-            _ => unreachable!("No valid direction"),
-        };
         for _i in 0..*len {
-            pos = pos.walk(dir);
-            points.insert(pos);
+            pos += *dir;
+            trench.set_at(pos, b'#');
         }
     }
-    let min_x = points.iter().map(|p| p.x).min().expect("one point");
-    let min_y = points.iter().map(|p| p.y).min().expect("one point");
-    let max_x = points.iter().map(|p| p.x).max().expect("one point");
-    let max_y = points.iter().map(|p| p.y).max().expect("one point");
-
-    let mut map = Map::new(max_x - min_x + 5, max_y - min_y + 5);
+    let (min, max) = trench.bounding_box().expect("one point");
 
-    map.add_boarder(b'O');
-
-    for pos in points {
-        let pos = Point {
-            x: pos.x + 2 - min_x,
-            y: pos.y + 2 - min_y,
-        };
-        map.set_at(pos, b'#');
-    }
+    // One empty row and column around the trench keeps the outside connected
+    let map = trench.to_map_area(min - Point { x: 1, y: 1 }, max + Point { x: 2, y: 2 }, b'.');
 
-    map.flood_cardinal(Point { x: 1, y: 1 }, b'.', b'O');
-    // map.print();
+    let (_, regions) = map.components(|_, c| c == b'.', Connectivity::Four);
+    let outside: usize = regions
+        .iter()
+        .filter(|region| region.touches_edge)
+        .map(|region| region.area)
+        .sum();
     SolutionType::try_from(map.get_width() * map.get_height()).expect("Number")
-        - SolutionType::try_from(map.find(b'O').len()).expect("non negative number")
+        - SolutionType::try_from(outside).expect("non negative number")
 }
 
-fn type_from_dirs(from: Dir, to: Dir) -> u8 {
-    use Dir::*;
+fn type_from_dirs(from: CardinalDir, to: CardinalDir) -> u8 {
+    use CardinalDir::*;
     match (from, to) {
         (North, North) | (South, South) => b'|',
         (East, East) | (West, West) => b'-',
@@ -107,7 +109,7 @@ fn area_for_y(y: i32, lines: &[(Point, u8, Point, u8)]) -> (u64, u64) {
         })
         .unique()
         .collect();
-    row.sort_by(|(from1, _), (from2, _)| from1.x.cmp(&from2.x));
+    row.sort_by_key(|(from, _)| from.x);
 
     let mut sum_extra_line = 0u64;
     let mut sum_below = 0u64;
@@ -196,29 +198,11 @@ pub fn solve_part2(_data: &[InputType]) -> SolutionType {
     let mut pos = Point { x: 0, y: 0 };
     let lines: Vec<_> = _data
         .iter()
-        .map(|(_, _, num)| {
-            let line = i32::try_from(num >> 4).expect("number");
+        .map(|&(_, _, (dir, len))| {
+            let line = i32::try_from(len).expect("number");
 
             let from = pos;
-            let dir = match num & 15 {
-                0 => {
-                    pos.x += line;
-                    Dir::East
-                }
-                1 => {
-                    pos.y += line;
-                    Dir::South
-                }
-                2 => {
-                    pos.x -= line;
-                    Dir::West
-                }
-                3 => {
-                    pos.y -= line;
-                    Dir::North
-                }
-                _ => unreachable!("Unknown dir"),
-            };
+            pos += Point::from(dir) * line;
             (from, pos, dir)
         })
         .collect();
@@ -259,3 +243,40 @@ pub fn solve_part2(_data: &[InputType]) -> SolutionType {
     }
     sum
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PLAN: &str = "R 6 (#70c710)
+D 5 (#0dc571)
+L 2 (#5713f0)
+D 2 (#d2c081)
+R 2 (#59c680)
+D 2 (#411b91)
+L 5 (#8ceee2)
+U 2 (#caa173)
+L 1 (#1b58a2)
+U 2 (#caa171)
+R 2 (#7807d2)
+U 3 (#a77fa3)
+L 2 (#015232)
+U 2 (#7a21e3)
+";
+
+    #[test]
+    fn example() {
+        let data = input_generator(PLAN).expect("Valid input");
+        assert_eq!(solve_part1(&data), 62);
+        assert_eq!(solve_part2(&data), 952408144115);
+    }
+
+    #[test]
+    fn invalid_direction_digit() {
+        let error = input_generator("R 6 (#70c710)\nD 5 (#0dc574)\n").expect_err("Digit 4");
+        assert_eq!(
+            (error.line, error.column, error.found.as_str()),
+            (2, 12, "4")
+        );
+    }
+}
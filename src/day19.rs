@@ -6,6 +6,8 @@ use aoc_runner_derive::{aoc, aoc_generator};
 use hashbrown::HashMap;
 use regex::Regex;
 
+use super::parse::{self, ParseError};
+
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum Action {
     Accept,
@@ -25,29 +27,33 @@ type InputType = (HashMap<String, Vec<Rule>>, Vec<[i16; 4]>);
 type SolutionType = usize;
 
 #[aoc_generator(day19)]
-pub fn input_generator(input: &str) -> InputType {
-    let workflow = Regex::new(r"([^{]+)\{(.+)\}").expect("Compilable regex");
-    let rule_re = Regex::new(r"(.+)([<>])([0-9]+):(.+)").expect("Compilable regex");
+pub fn input_generator(input: &str) -> Result<InputType, ParseError> {
+    let workflow = Regex::new(r"^([^{]+)\{(.+)\}$").expect("Compilable regex");
+    let rule_re = Regex::new(r"^(.+)([<>])([0-9]+):(.+)$").expect("Compilable regex");
+
+    let mut sections = parse::sections(input);
 
     // qqz{s>2770:qs,m<1801:hdj,R}
-    let workflows = input
+    let workflows = parse::next_section(input, &mut sections, "the workflows")?
         .lines()
-        .take_while(|s| !s.is_empty())
         .map(|s| {
-            let caps = workflow.captures(s).expect("Matching");
+            let caps = workflow
+                .captures(s)
+                .ok_or_else(|| ParseError::new(input, s, "a workflow"))?;
             let name = caps[1].to_string();
             let mut rules = vec![];
-            for rule in caps[2].split(',') {
+            for rule in caps.get(2).expect("Matched group").as_str().split(',') {
                 if let Some(caps) = rule_re.captures(rule) {
+                    let group = |i| caps.get(i).expect("Matched group").as_str();
                     let is_less_than = caps[2].eq("<");
-                    let field = match &caps[1] {
+                    let field = match group(1) {
                         "x" => 0,
                         "m" => 1,
                         "a" => 2,
                         "s" => 3,
-                        _ => unreachable!("Unknown field"),
+                        field => return Err(ParseError::new(input, field, "x, m, a or s")),
                     };
-                    let value = caps[3].parse().expect("comparision number");
+                    let value = parse::number(input, group(3))?;
                     let action;
                     if &caps[4] == "A" {
                         action = Action::Accept;
@@ -79,28 +85,26 @@ pub fn input_generator(input: &str) -> InputType {
                     });
                 }
             }
-            (name, rules)
+            Ok((name, rules))
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
     let re =
-        Regex::new(r"\{x=([0-9]*),m=([0-9]*),a=([0-9]*),s=([0-9]*)\}").expect("Compilable regex");
+        Regex::new(r"^\{x=([0-9]*),m=([0-9]*),a=([0-9]*),s=([0-9]*)\}$").expect("Compilable regex");
 
-    let parts = input
+    // {x=787,m=2655,a=1222,s=2876}
+    let parts = parse::next_section(input, &mut sections, "the parts")?
         .lines()
-        .filter_map(|s| {
-            re.captures(s).map(|cap| {
-                [
-                    cap[1].parse().expect("Number"),
-                    cap[2].parse().expect("Number"),
-                    cap[3].parse().expect("Number"),
-                    cap[4].parse().expect("Number"),
-                ]
-            })
+        .map(|s| {
+            let cap = re
+                .captures(s)
+                .ok_or_else(|| ParseError::new(input, s, "a part"))?;
+            let number = |i| parse::number(input, cap.get(i).expect("Matched group").as_str());
+            Ok([number(1)?, number(2)?, number(3)?, number(4)?])
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
 
-    (workflows, parts)
+    Ok((workflows, parts))
 }
 
 #[aoc(day19, part1)]
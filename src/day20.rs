@@ -5,6 +5,9 @@
 use aoc_runner_derive::{aoc, aoc_generator};
 use hashbrown::HashMap;
 use std::collections::VecDeque;
+
+use super::math::lcm;
+use super::parse::{self, ParseError};
 use ModuleType::*;
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -138,18 +141,25 @@ fn pulse(
 }
 
 #[aoc_generator(day20)]
-pub fn input_generator(input: &str) -> Vec<InputType> {
+pub fn input_generator(input: &str) -> Result<Vec<InputType>, ParseError> {
+    // %a -> inv, con
     input
         .lines()
         .map(|line| {
-            let line = line.split_once(" -> ").expect("->");
-            let destinations = line.1.split(", ").map(|s| s.to_string()).collect();
+            let line = parse::adjacency(input, line, " -> ", ",")?;
+            let destinations = line.1.iter().map(|s| s.to_string()).collect();
             if let Some(name) = line.0.strip_prefix('%') {
-                FlipFlop(name.to_string(), destinations)
+                Ok(FlipFlop(name.to_string(), destinations))
             } else if let Some(name) = line.0.strip_prefix('&') {
-                Conjunction(name.to_string(), destinations)
+                Ok(Conjunction(name.to_string(), destinations))
+            } else if line.0 == BROADCASTER {
+                Ok(Broadcaster(destinations))
             } else {
-                Broadcaster(destinations)
+                Err(ParseError::new(
+                    input,
+                    line.0,
+                    "%name, &name or broadcaster",
+                ))
             }
         })
         .collect()
@@ -170,24 +180,6 @@ pub fn solve_part1(data: &[InputType]) -> SolutionType {
     highs * lows
 }
 
-fn gcd(a: u64, b: u64) -> u64 {
-    let mut max = a.max(b);
-    let mut min = a.min(b);
-
-    loop {
-        let r = max % min;
-        if r == 0 {
-            return min;
-        }
-        max = min;
-        min = r;
-    }
-}
-
-fn lcm(a: u64, b: u64) -> u64 {
-    a * (b / gcd(a, b))
-}
-
 #[aoc(day20, part2)]
 pub fn solve_part2(data: &[InputType]) -> u64 {
     /*
@@ -240,3 +232,59 @@ pub fn solve_part2(data: &[InputType]) -> u64 {
         })
         .fold(1, lcm)
 }
+
+#[cfg(test)]
+mod test {
+    use super::super::world::graph::Graph;
+    use super::*;
+
+    /// The modules and where they send their pulses
+    fn module_graph(data: &[InputType]) -> Graph<String> {
+        let modules = modules_to_hashmap(data);
+        let label = |name: &str| match modules.get(name) {
+            Some(FlipFlop(_, _)) => format!("%{}", name),
+            Some(Conjunction(_, _)) => format!("&{}", name),
+            _ => name.to_string(),
+        };
+        // Sorted, as the order of the nodes is the order of the DOT output
+        let mut names: Vec<_> = modules.iter().collect();
+        names.sort_unstable_by_key(|&(&name, _)| name);
+        let mut graph = Graph::new();
+        for (&name, module) in names {
+            let dests = match module {
+                Broadcaster(dests) | FlipFlop(_, dests) | Conjunction(_, dests) => dests,
+            };
+            let from = graph.add_node(label(name));
+            for dest in dests {
+                let to = graph.add_node(label(dest));
+                graph.add_edge(from, to, 1);
+            }
+        }
+        graph
+    }
+
+    const EXAMPLE: &str = "broadcaster -> a, b, c
+%a -> b
+%b -> c
+%c -> inv
+&inv -> a
+";
+
+    #[test]
+    fn example() {
+        let data = input_generator(EXAMPLE).expect("Valid input");
+        assert_eq!(solve_part1(&data), 32000000);
+    }
+
+    /// Writes the module network to AOC_DOT_DIR, if it is set
+    #[test]
+    fn export_dot() {
+        let data = input_generator(EXAMPLE).expect("Valid input");
+        let graph = module_graph(&data);
+        assert_eq!(
+            graph.nodes(),
+            &["%a", "%b", "%c", "broadcaster", "&inv"].map(String::from)
+        );
+        graph.export_dot("day20", &[]).expect("Writable dot file");
+    }
+}
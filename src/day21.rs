@@ -4,45 +4,94 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use super::parse::{self, ParseError};
+use super::world::search::SearchProblem;
 use super::world::*;
 
 type SolutionType = usize;
 
-fn plots_after_steps(start: Point, map: &Map, steps: i32) -> usize {
-    use hashbrown::HashSet;
-    let mut found = [HashSet::new(), HashSet::new()];
-    let mut front = Vec::new();
-    front.push(start);
-    let mut next = Vec::new();
-    for gen in 0..=steps {
-        let is_even = gen % 2;
-        for &pos in &front {
-            if found[is_even as usize].insert(pos) {
-                use Dir::*;
-                for dir in [North, South, East, West] {
-                    let new_pos = pos.walk(dir);
-                    let c = map.get_at_unchecked(Point {
-                        x: new_pos.x.rem_euclid(map.get_width()),
-                        y: new_pos.y.rem_euclid(map.get_height()),
-                    });
-                    if c == b'.' || c == b'S' {
-                        next.push(new_pos);
-                    }
-                }
+struct GardenWalk<'a> {
+    map: TiledMap<'a, i32>,
+    start: Point,
+}
+
+impl<'a> SearchProblem for GardenWalk<'a> {
+    type State = Point;
+    type Cost = i32;
+
+    fn start_states(&self) -> Vec<Point> {
+        vec![self.start]
+    }
+
+    fn successors(&self, pos: &Point, successors: &mut Vec<(Point, i32)>) {
+        for (new_pos, _) in pos.neighbors4() {
+            let c = self.map.get_at(new_pos);
+            if c == b'.' || c == b'S' {
+                successors.push((new_pos, 1));
             }
         }
-        front.clear();
-        front.append(&mut next);
     }
-    found[steps as usize % 2]
-        .iter()
-        .filter(|&pos| map.is_inside_map(*pos))
+
+    fn is_goal(&self, _pos: &Point) -> bool {
+        false
+    }
+}
+
+/// All plots, on the infinitely repeated map, that can be reached after exactly steps
+fn reachable_plots(start: Point, map: &Map, steps: i32) -> impl Iterator<Item = Point> {
+    let walk = GardenWalk {
+        map: map.tiled(),
+        start,
+    };
+    // A plot can be reached in exactly steps if it can be reached in fewer steps
+    // with the same parity, by walking back and forth.
+    search::reachable_within(&walk, steps)
+        .into_iter()
+        .filter_map(move |(pos, distance)| (distance % 2 == steps % 2).then_some(pos))
+}
+
+fn plots_after_steps(start: Point, map: &Map, steps: i32) -> usize {
+    reachable_plots(start, map, steps)
+        .filter(|&pos| map.is_inside_map(pos))
         .count()
 }
 
+fn plots_after_steps_on_all_tiles(start: Point, map: &Map, steps: i32) -> usize {
+    reachable_plots(start, map, steps).count()
+}
+
+/// Once the walk has spread over enough copies of the map, the number of
+/// reachable plots grows quadratically for every period steps.
+///
+/// Simulates until that happens, so it works for any map and start position,
+/// but is a lot slower than the formula in solve_part2.
+fn extrapolate_plots(start: Point, map: &Map, steps: i32) -> SolutionType {
+    let period = num::integer::lcm(map.get_width(), map.get_height());
+    let mut steps_done = steps % period;
+    let mut plots = Vec::new();
+    loop {
+        plots.push(plots_after_steps_on_all_tiles(start, map, steps_done) as i64);
+        if steps_done == steps {
+            return plots[plots.len() - 1] as SolutionType;
+        }
+        if let [.., a, b, c, d, e] = plots[..] {
+            if c - 2 * b + a == d - 2 * c + b && d - 2 * c + b == e - 2 * d + c {
+                break;
+            }
+        }
+        steps_done += period;
+    }
+    let last = plots[plots.len() - 1];
+    let diff = last - plots[plots.len() - 2];
+    let second_diff = diff - (plots[plots.len() - 2] - plots[plots.len() - 3]);
+    let n = i64::from((steps - steps_done) / period);
+    let result = last + n * diff + second_diff * n * (n + 1) / 2;
+    SolutionType::try_from(result).expect("Positive number of plots")
+}
+
 #[aoc_generator(day21)]
-pub fn input_generator(input: &str) -> Map {
-    Map::from_string(input)
+pub fn input_generator(input: &str) -> Result<Map, ParseError> {
+    parse::map(input)
 }
 
 fn _print_map(map: &Map, found: &Vec<(Point, u32)>) {
@@ -58,9 +107,9 @@ fn _print_map(map: &Map, found: &Vec<(Point, u32)>) {
 
 #[aoc(day21, part1)]
 pub fn solve_part1(map: &Map) -> SolutionType {
-    let start = map.find(b'S')[0];
-
-    plots_after_steps(start, map, 64)
+    map.distance_field(&map.find(b'S'), |_, c| c == b'.')
+        .within_with_parity(64)
+        .count()
 }
 
 #[aoc(day21, part2)]
@@ -78,58 +127,88 @@ pub fn solve_part2(map: &Map) -> SolutionType {
     // and compared that with the result from this formula.
 
     let w = map.get_width();
-    assert_eq!(w, map.get_height());
-    assert_eq!((gen - w / 2) % w, 0);
+    let start = map.find(b'S')[0];
+    if w != map.get_height()
+        || start != (Point { x: w / 2, y: w / 2 })
+        || (gen - w / 2) % w != 0
+        || ((gen - w / 2) / w) % 2 != 0
+    {
+        return extrapolate_plots(start, map, gen);
+    }
 
     let n = (gen - w / 2) / w;
 
-    assert_eq!(n % 2, 0);
-
-    let start = Point { x: w / 2, y: w / 2 };
-
     let even = plots_after_steps(start, map, 3 * w - 1);
     let odd = plots_after_steps(start, map, 3 * w);
 
+    use Dir::*;
+    // The corners and the middle of the edges of the map
+    let corners =
+        [SouthEast, SouthWest, NorthWest, NorthEast].map(|dir| start + Point::from(dir) * (w / 2));
+    let edges = [East, South, West, North].map(|dir| start + Point::from(dir) * (w / 2));
+
     let steps_a = (3 * w - 3) / 2;
-    let a = [
-        plots_after_steps(Point { x: w - 1, y: w - 1 }, map, steps_a),
-        plots_after_steps(Point { x: 0, y: w - 1 }, map, steps_a),
-        plots_after_steps(Point { x: 0, y: 0 }, map, steps_a),
-        plots_after_steps(Point { x: w - 1, y: 0 }, map, steps_a),
-    ];
+    let a: SolutionType = corners
+        .iter()
+        .map(|&pos| plots_after_steps(pos, map, steps_a))
+        .sum();
     let steps_b = (w - 3) / 2;
-    let b = [
-        plots_after_steps(Point { x: w - 1, y: w - 1 }, map, steps_b),
-        plots_after_steps(Point { x: 0, y: w - 1 }, map, steps_b),
-        plots_after_steps(Point { x: 0, y: 0 }, map, steps_b),
-        plots_after_steps(Point { x: w - 1, y: 0 }, map, steps_b),
-    ];
+    let b: SolutionType = corners
+        .iter()
+        .map(|&pos| plots_after_steps(pos, map, steps_b))
+        .sum();
 
     let steps_t = w - 1;
-    let t = [
-        plots_after_steps(
-            Point {
-                x: w - 1,
-                y: start.y,
-            },
-            map,
-            steps_t,
-        ),
-        plots_after_steps(
-            Point {
-                x: start.x,
-                y: w - 1,
-            },
-            map,
-            steps_t,
-        ),
-        plots_after_steps(Point { x: 0, y: start.y }, map, steps_t),
-        plots_after_steps(Point { x: start.x, y: 0 }, map, steps_t),
-    ];
+    let t: SolutionType = edges
+        .iter()
+        .map(|&pos| plots_after_steps(pos, map, steps_t))
+        .sum();
 
     ((n - 1) as SolutionType * (n - 1) as SolutionType) * odd
         + n as SolutionType * n as SolutionType * even
-        + (n - 1) as SolutionType * (a[0] + a[1] + a[2] + a[3])
-        + n as SolutionType * (b[0] + b[1] + b[2] + b[3])
-        + (t[0] + t[1] + t[2] + t[3])
+        + (n - 1) as SolutionType * a
+        + n as SolutionType * b
+        + t
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const GARDEN: &str = "...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........
+";
+
+    #[test]
+    fn extrapolate_the_example() {
+        let map = Map::from_string(GARDEN);
+        let start = map.find(b'S')[0];
+        assert_eq!(extrapolate_plots(start, &map, 100), 6536);
+        assert_eq!(extrapolate_plots(start, &map, 500), 167004);
+    }
+
+    #[test]
+    fn extrapolate_without_the_closed_form() {
+        // Not square and the start is off center, so solve_part2 can't use
+        // its formula
+        let map = Map::from_string(".....\n.#S..\n...#.\n");
+        let start = map.find(b'S')[0];
+        for steps in [151, 200] {
+            assert_eq!(
+                extrapolate_plots(start, &map, steps),
+                plots_after_steps_on_all_tiles(start, &map, steps),
+                "After {} steps",
+                steps
+            );
+        }
+    }
 }
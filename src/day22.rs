@@ -4,144 +4,84 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use super::parse::{self, ParseError};
+use super::world::*;
 use hashbrown::HashSet;
 
-type Point = (i16, i16, i16);
-type InputType = (Point, Point);
+type Brick = Cuboid<i16>;
+type InputType = Brick;
 type SolutionType = usize;
 
-fn make_point(s: &str) -> Point {
-    let v: Vec<_> = s.split(',').map(|n| n.parse().expect("number")).collect();
-    (v[0], v[1], v[2])
+fn make_point([x, y, z]: [i16; 3]) -> Point3<i16> {
+    Point3::new(x, y, z)
 }
 
 #[aoc_generator(day22)]
-pub fn input_generator(input: &str) -> Vec<InputType> {
+pub fn input_generator(input: &str) -> Result<Vec<InputType>, ParseError> {
     // 1,0,1~1,2,1
     input
         .lines()
         .map(|line| {
-            let line = line.split_once('~').expect("~");
-            (make_point(line.0), make_point(line.1))
+            let (from, to) = parse::tuple_pair(input, line, "~", ',')?;
+            Ok(Cuboid::new(make_point(from), make_point(to)))
         })
         .collect()
 }
 
-fn would_hit(mut a_min: Point, mut a_max: Point, b_min: Point, b_max: Point) -> bool {
-    a_min.2 -= 1;
-    a_max.2 -= 1;
-
-    a_min.0 <= b_max.0
-        && a_max.0 >= b_min.0
-        && a_min.1 <= b_max.1
-        && a_max.1 >= b_min.1
-        && a_min.2 <= b_max.2
-        && a_max.2 >= b_min.2
-}
-
-fn settle_bricks(data: &mut Vec<(Point, Point, bool)>) {
-    data.sort_by(|a, b| a.2.cmp(&b.2));
-    let mut first = 0;
-    let mut last = data.len();
-    while first < last {
-        let mut any_moved = false;
-        for i in first..last {
-            if data[i].2 {
-                // Its grounded
-                continue;
-            }
-            let mut can_fall = true;
-            while can_fall {
-                if data[i].0 .2 == 1 {
-                    // Can't fall below ground
-                    data[i].2 = true;
-                    if i == first {
-                        first += 1;
-                    }
-                    break;
-                }
-                for j in 0..data.len() {
-                    if i != j && would_hit(data[i].0, data[i].1, data[j].0, data[j].1) {
-                        if data[j].2 {
-                            data[i].2 = true;
-                            if i == first {
-                                first += 1;
-                            }
-                        }
-                        can_fall = false;
-                        break;
-                    }
-                }
-                if can_fall {
-                    data[i].0 .2 -= 1;
-                    data[i].1 .2 -= 1;
-                    any_moved = true;
-                }
+/// Lets all bricks fall until they land on the ground (z = 0) or on another brick
+///
+/// Returns, for every brick, the bricks it rests on.
+fn settle_bricks(bricks: &[Brick]) -> Vec<Vec<usize>> {
+    let mut bricks = bricks.to_vec();
+    bricks.sort_by_key(|brick| brick.min.z);
+    let bounds = bricks
+        .iter()
+        .fold(bricks[0], |bounds, brick| bounds.union(brick));
+    let mut space = VoxelMap::new(bounds, None);
+
+    let mut supported_by = Vec::with_capacity(bricks.len());
+    for (i, brick) in bricks.iter_mut().enumerate() {
+        let supporters = loop {
+            let below = brick.walk(Dir3::Down);
+            if below.min.z < 1 {
+                break Vec::new();
             }
-            if !data[i].2 {
-                last = i + 1;
+            let mut supporters: Vec<_> = space.values_in(&below).flatten().collect();
+            if !supporters.is_empty() {
+                supporters.sort();
+                supporters.dedup();
+                break supporters;
             }
-        }
-        if !any_moved {
-            break;
+            *brick = below;
+        };
+        space.fill(brick, Some(i));
+        supported_by.push(supporters);
+    }
+    supported_by
+}
+
+fn supports_from(supported_by: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    let mut supports = vec![vec![]; supported_by.len()];
+    for (j, supporters) in supported_by.iter().enumerate() {
+        for &i in supporters {
+            supports[i].push(j);
         }
     }
+    supports
 }
 
 #[aoc(day22, part1)]
 pub fn solve_part1(data: &[InputType]) -> SolutionType {
-    // First point <= second point
-    let mut data: Vec<_> = data.iter().map(|(p1, p2)| (*p1, *p2, p1.2 == 1)).collect();
+    let supported_by = settle_bricks(data);
 
-    settle_bricks(&mut data);
+    // A brick is needed if it is the only support of another brick
+    let needed: HashSet<_> = supported_by
+        .iter()
+        .filter(|supporters| supporters.len() == 1)
+        .map(|supporters| supporters[0])
+        .collect();
 
-    let mut supports = vec![];
-    for _ in 0..data.len() {
-        supports.push(vec![]);
-    }
-    for i in 0..data.len() {
-        for j in 0..data.len() {
-            if i == j {
-                continue;
-            }
-            if !would_hit(data[j].0, data[j].1, data[i].0, data[i].1) {
-                continue;
-            }
-            supports[i].push(j);
-        }
-    }
-    let mut count = 0;
-    for i in 0..data.len() {
-        if supports[i].is_empty() {
-            // println!("{} doesn't support any at all", (b'A' + i as u8) as char);
-            count += 1;
-            continue;
-        }
-        let mut is_needed = false;
-        for j in &supports[i] {
-            let mut has_other_support = false;
-            for (k, support) in supports.iter().enumerate() {
-                if k == i || k == *j {
-                    continue;
-                }
-                if support.contains(j) {
-                    // Another supports j.
-                    has_other_support = true;
-                    break;
-                }
-            }
-            if !has_other_support {
-                is_needed = true;
-                break;
-            }
-        }
-        if !is_needed {
-            // println!( "{} can be removed, its dependees have other supports", (b'A' + i as u8) as char);
-            count += 1;
-        }
-    }
-
-    count
+    supported_by.len() - needed.len()
 }
 
 fn fallcount(supports: &[Vec<usize>], supported_by: &[Vec<usize>], i: usize) -> SolutionType {
@@ -170,34 +110,35 @@ fn fallcount(supports: &[Vec<usize>], supported_by: &[Vec<usize>], i: usize) ->
 
 #[aoc(day22, part2)]
 pub fn solve_part2(data: &[InputType]) -> SolutionType {
-    let mut data: Vec<_> = data.iter().map(|(p1, p2)| (*p1, *p2, p1.2 == 1)).collect();
-
-    settle_bricks(&mut data);
-
-    let mut supports = vec![];
-    let mut supported_by = vec![];
-    for _ in 0..data.len() {
-        supports.push(vec![]);
-        supported_by.push(vec![]);
-    }
-    for i in 0..data.len() {
-        for j in 0..data.len() {
-            if i == j {
-                continue;
-            }
-            if !would_hit(data[j].0, data[j].1, data[i].0, data[i].1) {
-                continue;
-            }
-            supports[i].push(j);
-            supported_by[j].push(i);
-        }
-    }
+    let supported_by = settle_bricks(data);
+    let supports = supports_from(&supported_by);
 
     let mut count = 0;
-    for i in 0..data.len() {
+    for i in 0..supported_by.len() {
         let res = fallcount(&supports, &supported_by, i) - 1;
         // println!("{} {}", i, res);
         count += res;
     }
     count
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9
+";
+
+    #[test]
+    fn example() {
+        let data = input_generator(EXAMPLE).expect("Valid input");
+        assert_eq!(solve_part1(&data), 5);
+        assert_eq!(solve_part2(&data), 7);
+    }
+}
@@ -4,21 +4,32 @@
 
 use aoc_runner_derive::{aoc, aoc_generator};
 
+use super::parse::{self, ParseError};
+use super::world::graph::Graph;
 use super::world::*;
-use hashbrown::{HashMap, HashSet};
-use Dir::*;
 
 type SolutionType = usize;
 
 #[aoc_generator(day23)]
-pub fn input_generator(input: &str) -> Map {
-    Map::from_string_with_border(input)
+pub fn input_generator(input: &str) -> Result<Map, ParseError> {
+    parse::map_with_border(input)
 }
 
 fn is_walkable(c: u8) -> bool {
     matches!(c, b'.' | b'<' | b'>' | b'^' | b'v')
 }
 
+/// The direction a slope can be walked in
+fn slope(c: u8) -> Option<CardinalDir> {
+    match c {
+        b'^' => Some(CardinalDir::North),
+        b'>' => Some(CardinalDir::East),
+        b'v' => Some(CardinalDir::South),
+        b'<' => Some(CardinalDir::West),
+        _ => None,
+    }
+}
+
 fn is_node(map: &Map, pos: Point, c: u8) -> bool {
     if pos == (Point { x: 2, y: 1 })
         || pos
@@ -31,14 +42,11 @@ fn is_node(map: &Map, pos: Point, c: u8) -> bool {
     }
     if is_walkable(c) {
         let mut count = 0;
-        for dir in [North, South, East, West] {
-            let new_pos = pos.walk(dir);
-            if let Some(c) = map.get_at(new_pos) {
-                if is_walkable(c) {
-                    count += 1;
-                    if count > 2 {
-                        return true;
-                    }
+        for (_, _, c) in map.neighbors4(pos) {
+            if is_walkable(c) {
+                count += 1;
+                if count > 2 {
+                    return true;
                 }
             }
         }
@@ -46,126 +54,93 @@ fn is_node(map: &Map, pos: Point, c: u8) -> bool {
     false
 }
 
+/// The graph between the start, the goal and all junctions
+fn junction_graph(map: &Map, slippery: bool) -> Graph<Point> {
+    map.compress_to_graph(
+        |_, c| is_walkable(c),
+        |pos, c| is_node(map, pos, c),
+        // Slopes can only be walked in their direction
+        |_, dir, c| !slippery || slope(c).is_none_or(|slope| slope == dir),
+    )
+}
+
+/// The ids of the start and goal nodes
+fn ends(map: &Map, graph: &Graph<Point>) -> (usize, usize) {
+    let start = graph.id(&Point { x: 2, y: 1 }).expect("Start node added");
+    let goal = graph
+        .id(&Point {
+            x: map.get_width() - 3,
+            y: map.get_height() - 2,
+        })
+        .expect("Goal node added");
+    (start, goal)
+}
+
+fn longest_path(map: &Map, graph: &Graph<Point>) -> SolutionType {
+    let (start, goal) = ends(map, graph);
+    let most_steps = graph.longest_path(start, goal).expect("A path to the goal");
+    SolutionType::try_from(most_steps).expect("Answer")
+}
+
 #[aoc(day23, part1)]
 pub fn solve_part1(map: &Map) -> SolutionType {
-    let start = Point { x: 2, y: 1 };
-    let goal = Point {
-        x: map.get_width() - 3,
-        y: map.get_height() - 2,
-    };
-
-    let mut frontier = Vec::new();
-    frontier.push((HashSet::new(), start));
-    let mut most_steps = 0;
-    while let Some((mut steps, pos)) = frontier.pop() {
-        if !steps.insert(pos) {
-            continue;
-        }
-        if pos == goal {
-            most_steps = most_steps.max(steps.len() - 1);
-        }
-        for dir in [North, South, East, West] {
-            if matches!(
-                (map.get_at_unchecked(pos), dir),
-                (b'.', _) | (b'^', North) | (b'v', South) | (b'>', East) | (b'<', West)
-            ) {
-                let new_pos = pos.walk(dir);
-                if is_walkable(map.get_at_unchecked(new_pos)) {
-                    frontier.push((steps.clone(), new_pos));
-                }
-            }
-        }
-    }
-
-    most_steps
+    longest_path(map, &junction_graph(map, true))
 }
 
 #[aoc(day23, part2)]
 pub fn solve_part2(map: &Map) -> SolutionType {
-    let start = Point { x: 2, y: 1 };
-    let goal = Point {
-        x: map.get_width() - 3,
-        y: map.get_height() - 2,
-    };
-
-    let nodes: Vec<_> = map
-        .iter()
-        .filter_map(|(pos, c)| {
-            if is_node(map, pos, c) {
-                Some(pos)
-            } else {
-                Option::None
-            }
-        })
-        .collect();
-
-    let pos_to_node: HashMap<_, _> = nodes
-        .iter()
-        .enumerate()
-        .map(|(k, &v)| (v, u8::try_from(k).expect("fits")))
-        .collect();
-
-    /*
-    map.print_with_overlay(|pos, _c| {
-        if pos_to_node.contains_key(&pos) {
-            Some(b'X')
-        } else {
-            Option::None
-        }
-    });
-    */
-
-    let start = *pos_to_node.get(&start).expect("Start node added");
-    let goal = *pos_to_node.get(&goal).expect("Goal node added");
-
-    let edges: Vec<_> = nodes
-        .iter()
-        .map(|&pos| {
-            let mut frontier = Vec::new();
-            let mut edges = Vec::new();
-            for dir in [North, South, East, West] {
-                let new_pos = pos.walk(dir);
-                if is_walkable(map.get_at_unchecked(new_pos)) {
-                    frontier.push((0, pos, new_pos));
-                }
-            }
-            while let Some((mut steps, old_pos, pos)) = frontier.pop() {
-                steps += 1;
-                if let Some(next_node) = pos_to_node.get(&pos) {
-                    edges.push((next_node, steps));
-                } else {
-                    for dir in [North, South, East, West] {
-                        let new_pos = pos.walk(dir);
-                        if new_pos != old_pos && is_walkable(map.get_at_unchecked(new_pos)) {
-                            frontier.push((steps, pos, new_pos));
-                        }
-                    }
-                }
-            }
-            edges
-        })
-        .collect();
-
-    let mut frontier = Vec::new();
-    let mut visited = HashSet::new();
-    frontier.push((0i32, start));
-    let mut most_steps = 0;
-    while let Some((steps, node)) = frontier.pop() {
-        if steps == -1 {
-            visited.remove(&node);
-            continue;
-        }
-        if !visited.insert(node) {
-            continue;
-        }
-        if node == goal {
-            most_steps = most_steps.max(steps);
-        }
-        frontier.push((-1, node));
-        for (&new_node, new_steps) in &edges[node as usize] {
-            frontier.push((steps + *new_steps, new_node));
-        }
+    longest_path(map, &junction_graph(map, false))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "#.#####################
+#.......#########...###
+#######.#########.#.###
+###.....#.>.>.###.#.###
+###v#####.#v#.###.#.###
+###.>...#.#.#.....#...#
+###v###.#.#.#########.#
+###...#.#.#.......#...#
+#####.#.#.#######.#.###
+#.....#.#.#.......#...#
+#.#####.#.#.#########v#
+#.#...#...#...###...>.#
+#.#.#v#######v###.###v#
+#...#.>.#...>.>.#.###.#
+#####v#.#.###v#.#.###.#
+#.....#...#...#.#.#...#
+#.#########.###.#.#.###
+#...###...#...#...#.###
+###.###.#.###v#####v###
+#...#...#.#.>.>.#.>.###
+#.###.###.#.###.#.#v###
+#.....###...###...#...#
+#####################.#
+";
+
+    #[test]
+    fn example() {
+        let map = input_generator(EXAMPLE).expect("Valid input");
+        assert_eq!(solve_part1(&map), 94);
+        assert_eq!(solve_part2(&map), 154);
     }
 
-    SolutionType::try_from(most_steps).expect("Answer")
+    /// Writes the junction graph, with the longest hike, to AOC_DOT_DIR, if it is set
+    #[test]
+    fn export_dot() {
+        let map = input_generator(EXAMPLE).expect("Valid input");
+        let graph = junction_graph(&map, false);
+        let (start, goal) = ends(&map, &graph);
+        let (steps, path) = graph.longest_path_nodes(start, goal).expect("A path");
+        assert_eq!(steps, 154);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&goal));
+        let edges: Vec<_> = path.windows(2).map(|step| (step[0], step[1])).collect();
+        graph
+            .export_dot("day23", &edges)
+            .expect("Writable dot file");
+    }
 }
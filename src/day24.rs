@@ -5,6 +5,8 @@
 use aoc_runner_derive::{aoc, aoc_generator};
 use num::*;
 
+use super::parse::{self, ParseError};
+
 // use crate::world::LengthType;
 
 type Length = f64;
@@ -31,27 +33,20 @@ pub struct InputType {
 type SolutionType = usize;
 
 #[aoc_generator(day24)]
-pub fn input_generator(input: &str) -> Vec<InputType> {
+pub fn input_generator(input: &str) -> Result<Vec<InputType>, ParseError> {
     // 1, 2, 3 @ -1, 2, -3
     input
         .lines()
         .map(|line| {
-            let line: Vec<_> = line
-                .split_ascii_whitespace()
-                .filter_map(|s| s.trim_end_matches(',').parse().ok())
-                .collect();
-            InputType {
-                p: Point {
-                    x: line[0],
-                    y: line[1],
-                    z: line[2],
-                },
+            let ([x, y, z], [dx, dy, dz]) = parse::tuple_pair(input, line, "@", ',')?;
+            Ok(InputType {
+                p: Point { x, y, z },
                 v: Dir {
-                    x: line[3],
-                    y: line[4],
-                    z: line[5],
+                    x: dx,
+                    y: dy,
+                    z: dz,
                 },
-            }
+            })
         })
         .collect()
 }
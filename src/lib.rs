@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: GPL-3.0-or-later
 use aoc_runner_derive::aoc_lib;
 
+pub mod cycle;
 mod day01;
 mod day02;
 mod day03;
@@ -28,6 +29,8 @@ mod day22;
 mod day23;
 mod day24;
 mod day25;
+pub mod math;
+pub mod parse;
 pub mod world;
 
 aoc_lib! { year = 2023 }
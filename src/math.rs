@@ -0,0 +1,8 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+mod number_theory;
+pub mod quadratic;
+
+pub use number_theory::{crt, extended_gcd, gcd, lcm, mod_inverse};
@@ -0,0 +1,158 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use num::{CheckedMul, Integer, Signed};
+
+/// The greatest common divisor, which is zero only if both are zero
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    a.gcd(&b)
+}
+
+/// The least common multiple, which is zero if either is zero
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    a.lcm(&b)
+}
+
+/// The gcd of a and b, together with x and y where a*x + b*y = gcd
+///
+/// The gcd is never negative.
+pub fn extended_gcd<T: Integer + Signed + Clone>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::one(), T::zero());
+    let (mut old_y, mut y) = (T::zero(), T::one());
+    while !r.is_zero() {
+        let q = old_r.div_floor(&r);
+        (old_r, r) = (r.clone(), old_r - q.clone() * r);
+        (old_x, x) = (x.clone(), old_x - q.clone() * x);
+        (old_y, y) = (y.clone(), old_y - q * y);
+    }
+    if old_r.is_negative() {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The x in 0..modulus where a*x ≡ 1 (mod modulus)
+///
+/// Gives None if a and modulus share a factor.
+pub fn mod_inverse<T: Integer + Signed + Clone>(a: T, modulus: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(a, modulus.clone());
+    if g.is_one() {
+        Some(x.mod_floor(&modulus))
+    } else {
+        None
+    }
+}
+
+/// Solves x ≡ offset (mod period) for all (offset, period) pairs
+///
+/// The periods don't need to be coprime. Gives the smallest non-negative x
+/// and the period of all solutions, or None if the congruences conflict,
+/// a period isn't positive or the period of all solutions doesn't fit in T.
+pub fn crt<T: Integer + Signed + CheckedMul + Clone>(
+    congruences: impl IntoIterator<Item = (T, T)>,
+) -> Option<(T, T)> {
+    congruences
+        .into_iter()
+        .try_fold((T::zero(), T::one()), |(offset, period), (a, n)| {
+            if !n.is_positive() {
+                return None;
+            }
+            let a = a.mod_floor(&n);
+            let (g, p, _) = extended_gcd(period.clone(), n.clone());
+            let diff = a - offset.clone();
+            if !diff.is_multiple_of(&g) {
+                return None;
+            }
+            // offset + period * k ≡ a (mod n), where period * p ≡ g (mod n)
+            let n_g = n / g.clone();
+            let k = mul_mod((diff / g).mod_floor(&n_g), p.mod_floor(&n_g), &n_g);
+            let combined = period.checked_mul(&n_g)?;
+            // k < n_g, so this is less than combined
+            Some(((offset + period * k).mod_floor(&combined), combined))
+        })
+}
+
+/// a*b mod modulus, for a and b in 0..modulus, even when a*b doesn't fit in T
+fn mul_mod<T: Integer + CheckedMul + Clone>(mut a: T, mut b: T, modulus: &T) -> T {
+    if let Some(product) = a.checked_mul(&b) {
+        return product.mod_floor(modulus);
+    }
+    // Double and add, where no sum may exceed the modulus
+    let add_mod = |x: T, y: T| {
+        let room = modulus.clone() - y.clone();
+        if x >= room {
+            x - room
+        } else {
+            x + y
+        }
+    };
+    let two = T::one() + T::one();
+    let mut product = T::zero();
+    while !b.is_zero() {
+        if b.is_odd() {
+            product = add_mod(product, a.clone());
+        }
+        a = add_mod(a.clone(), a);
+        b = b / two.clone();
+    }
+    product
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn divisors() {
+        assert_eq!(gcd(12u64, 18), 6);
+        assert_eq!(gcd(0u64, 7), 7);
+        assert_eq!(gcd(0u64, 0), 0);
+        assert_eq!(lcm(4u64, 6), 12);
+        assert_eq!(lcm(0u64, 6), 0);
+
+        let (g, x, y) = extended_gcd(240i64, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        let (g, x, y) = extended_gcd(-4i64, 6);
+        assert_eq!(g, 2);
+        assert_eq!(-4 * x + 6 * y, 2);
+
+        assert_eq!(mod_inverse(3i64, 11), Some(4));
+        assert_eq!(mod_inverse(6i64, 9), None);
+    }
+
+    #[test]
+    fn chinese_remainders() {
+        assert_eq!(crt([(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Periods sharing a factor
+        assert_eq!(crt([(3i64, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt([(1i64, 4), (2, 6)]), None);
+        assert_eq!(crt([(-1i64, 5)]), Some((4, 5)));
+        assert_eq!(crt(Vec::<(i64, i64)>::new()), Some((0, 1)));
+    }
+
+    #[test]
+    fn chinese_remainders_without_overflow() {
+        assert_eq!(crt([(1i64, 0)]), None);
+        assert_eq!(crt([(2i64, 3), (1, -5)]), None);
+        // Coprime periods whose product is too large for i64
+        let big = (1i64 << 62) - 57;
+        let other = (1i64 << 61) - 1;
+        assert_eq!(crt([(1i64, big), (2, other)]), None);
+        // The same in i128, where it fits
+        let (x, period) = crt([(1i128, big.into()), (2, other.into())]).expect("A solution");
+        assert_eq!(period, i128::from(big) * i128::from(other));
+        assert_eq!((x % i128::from(big), x % i128::from(other)), (1, 2));
+        // A period that fits, even though k's product doesn't
+        assert_eq!(
+            crt([(1i64, 3), ((1 << 61) - 6, (1 << 61) - 1)]),
+            Some((6917529027641081848, 6917529027641081853))
+        );
+        assert_eq!(mul_mod(big - 1, big - 2, &big), 2);
+        // Repeating a large period doesn't grow it
+        assert_eq!(crt([(5i64, big), (5, big)]), Some((5, big)));
+    }
+}
@@ -0,0 +1,136 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+//! Exact integer solutions of quadratic inequalities
+//!
+//! The roots are found with integer square roots, so they work for i64,
+//! i128 and BigInt without the rounding errors of f64. b² and 4ac must fit
+//! in the type. Unsigned types can't hold the negative coefficients, so
+//! u64 and u128 values are given to negative_widened and positive_widened,
+//! which work in BigInt.
+
+use num::integer::Roots;
+use num::{BigInt, Integer, Signed};
+use std::ops::RangeInclusive;
+
+/// The value of a*x² + b*x + c
+pub fn evaluate<T: Integer + Clone>(a: &T, b: &T, c: &T, x: &T) -> T {
+    (a.clone() * x.clone() + b.clone()) * x.clone() + c.clone()
+}
+
+/// The integers x where a*x² + b*x + c < 0
+///
+/// a must be positive. Gives None if there are no such integers.
+pub fn negative<T>(a: &T, b: &T, c: &T) -> Option<RangeInclusive<T>>
+where
+    T: Integer + Signed + Roots + Clone,
+{
+    assert!(a.is_positive(), "The parabola opens upwards");
+    let two = T::one() + T::one();
+    let discriminant = b.clone() * b.clone() - two.clone() * two.clone() * a.clone() * c.clone();
+    if !discriminant.is_positive() {
+        return None;
+    }
+    let root = discriminant.sqrt();
+    let denominator = two * a.clone();
+    let is_negative = |x: &T| evaluate(a, b, c, x).is_negative();
+
+    // The square root is rounded down, so both bounds are at most one off
+    let mut min = (-b.clone() - root.clone()).div_floor(&denominator);
+    let mut max = (-b.clone() + root).div_floor(&denominator) + T::one();
+    while min <= max && !is_negative(&min) {
+        min = min + T::one();
+    }
+    while max >= min && !is_negative(&max) {
+        max = max - T::one();
+    }
+    if min > max {
+        None
+    } else {
+        Some(min..=max)
+    }
+}
+
+/// The integers x where a*x² + b*x + c > 0
+///
+/// a must be negative. Gives None if there are no such integers.
+pub fn positive<T>(a: &T, b: &T, c: &T) -> Option<RangeInclusive<T>>
+where
+    T: Integer + Signed + Roots + Clone,
+{
+    negative(&-a.clone(), &-b.clone(), &-c.clone())
+}
+
+/// Like negative, but for coefficients of any integer types, such as u128
+pub fn negative_widened(
+    a: impl Into<BigInt>,
+    b: impl Into<BigInt>,
+    c: impl Into<BigInt>,
+) -> Option<RangeInclusive<BigInt>> {
+    negative(&a.into(), &b.into(), &c.into())
+}
+
+/// Like positive, but for coefficients of any integer types, such as u128
+pub fn positive_widened(
+    a: impl Into<BigInt>,
+    b: impl Into<BigInt>,
+    c: impl Into<BigInt>,
+) -> Option<RangeInclusive<BigInt>> {
+    positive(&a.into(), &b.into(), &c.into())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use num::BigInt;
+
+    #[test]
+    fn integer_intervals() {
+        // (x - 2)(x - 5) = x² - 7x + 10
+        assert_eq!(negative(&1i64, &-7, &10), Some(3..=4));
+        // (2x + 1)(x - 3) = 2x² - 5x - 3
+        assert_eq!(negative(&2i64, &-5, &-3), Some(0..=2));
+        assert_eq!(positive(&-2i64, &5, &3), Some(0..=2));
+        // Touches zero at x = 3
+        assert_eq!(negative(&1i64, &-6, &9), None);
+        // Roots at 2.2 and 2.8
+        assert_eq!(negative(&25i64, &-125, &154), None);
+        assert_eq!(negative(&1i64, &0, &1), None);
+    }
+
+    #[test]
+    fn large_intervals() {
+        // Roots at 2^61 ± 1, which f64 can't tell apart from 2^61
+        let t = 1i128 << 62;
+        let c = (t / 2 - 1) * (t / 2 + 1);
+        assert_eq!(negative(&1, &-t, &c), Some(t / 2..=t / 2));
+        assert_eq!(negative(&1, &-t, &(c - 1)), Some(t / 2 - 1..=t / 2 + 1));
+
+        let t = BigInt::from(1) << 200usize;
+        let half: BigInt = &t / 2;
+        let c = (&half - 1) * (&half + 1) - 1;
+        assert_eq!(
+            negative(&BigInt::from(1), &-t, &c),
+            Some(&half - 1..=&half + 1)
+        );
+    }
+
+    #[test]
+    fn unsigned_intervals() {
+        // x * (7 - x) > 10
+        assert_eq!(
+            positive_widened(-1, 7u64, -BigInt::from(10u64)),
+            Some(BigInt::from(3)..=BigInt::from(4))
+        );
+
+        // x * (t - x) > d only at the top of the parabola, around 2^127
+        let t = u128::MAX;
+        let top = 1u128 << 127;
+        let d: BigInt = BigInt::from(top - 1) * top - 1;
+        let range = negative_widened(1, -BigInt::from(t), d.clone()).expect("Two values");
+        assert_eq!(range, BigInt::from(top - 1)..=BigInt::from(top));
+        assert_eq!(u128::try_from(range.end()), Ok(top));
+        assert_eq!(negative_widened(1, -BigInt::from(t), d + 1), None);
+    }
+}
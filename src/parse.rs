@@ -0,0 +1,401 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::world::MapParseError;
+
+/// Why the puzzle input couldn't be parsed
+///
+/// Line and column numbers start at 1.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    /// What was expected
+    pub expected: String,
+    /// What was found instead
+    pub found: String,
+}
+
+impl ParseError {
+    /// An error for found, which must be a part of input
+    pub fn new(input: &str, found: &str, expected: impl Into<String>) -> Self {
+        let (line, column) = position(input, found);
+        Self {
+            line,
+            column,
+            expected: expected.into(),
+            found: found.to_string(),
+        }
+    }
+
+    /// The error of a map parsed from map_text, which must be a part of input
+    pub fn from_map_error(input: &str, map_text: &str, error: MapParseError) -> Self {
+        let (first_line, _) = position(input, map_text);
+        let lines: Vec<_> = map_text.lines().collect();
+        let at = |line: usize, column: usize| (first_line + line - 1, column);
+        let ((line, column), expected, found) = match error {
+            MapParseError::Empty => (at(1, 1), "a map".to_string(), String::new()),
+            MapParseError::RaggedRow {
+                line,
+                expected,
+                found,
+            } => (
+                at(line, 1),
+                format!("a row of {} characters", expected),
+                format!("{} characters: {}", found, lines[line - 1]),
+            ),
+            MapParseError::NonAscii { line, column } => (
+                at(line, column),
+                "an ASCII character".to_string(),
+                lines[line - 1].to_string(),
+            ),
+            MapParseError::UnknownCell { line, column, c } => (
+                at(line, column),
+                "a known map character".to_string(),
+                c.to_string(),
+            ),
+            MapParseError::TooLarge { width, height } => (
+                at(1, 1),
+                "a smaller map".to_string(),
+                format!("{}x{}", width, height),
+            ),
+        };
+        Self {
+            line,
+            column,
+            expected,
+            found,
+        }
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Line {}, column {}: expected {}, found \"{}\"",
+            self.line, self.column, self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// The line and column where part starts in input
+fn position(input: &str, part: &str) -> (usize, usize) {
+    let offset = (part.as_ptr() as usize)
+        .checked_sub(input.as_ptr() as usize)
+        .filter(|&offset| offset <= input.len())
+        .expect("The part is in the input");
+    let before = &input[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// Parses text, which must be a part of input, as a number
+pub fn number<T: std::str::FromStr>(input: &str, text: &str) -> Result<T, ParseError> {
+    text.parse()
+        .map_err(|_| ParseError::new(input, text, "a number"))
+}
+
+/// Splits text, which must be a part of input, at the first separator
+pub fn split_once<'a>(
+    input: &str,
+    text: &'a str,
+    separator: &str,
+) -> Result<(&'a str, &'a str), ParseError> {
+    text.split_once(separator)
+        .ok_or_else(|| ParseError::new(input, text, format!("\"{}\"", separator)))
+}
+
+/// Removes prefix from text, which must be a part of input
+pub fn strip_prefix<'a>(input: &str, text: &'a str, prefix: &str) -> Result<&'a str, ParseError> {
+    text.strip_prefix(prefix)
+        .ok_or_else(|| ParseError::new(input, text, format!("\"{}\"", prefix)))
+}
+
+/// The next line of lines, which are the lines of input
+pub fn next_line<'a>(
+    input: &'a str,
+    lines: &mut impl Iterator<Item = &'a str>,
+    expected: &str,
+) -> Result<&'a str, ParseError> {
+    lines
+        .next()
+        .ok_or_else(|| ParseError::new(input, &input[input.len()..], expected))
+}
+
+/// The blank line separated sections of text
+///
+/// Lines with only whitespace are blank, so CRLF line endings work too.
+pub fn sections(text: &str) -> impl Iterator<Item = &str> {
+    let is_blank = |line: &str| line.trim().is_empty();
+    let mut lines = text.split_inclusive('\n').peekable();
+    std::iter::from_fn(move || {
+        while lines.next_if(|line| is_blank(line)).is_some() {}
+        let first = lines.next()?;
+        let start = first.as_ptr() as usize - text.as_ptr() as usize;
+        let mut end = start + first.len();
+        while let Some(line) = lines.next_if(|line| !is_blank(line)) {
+            end += line.len();
+        }
+        Some(text[start..end].trim_end_matches(['\r', '\n']))
+    })
+}
+
+/// The next section of sections, which are the sections of input
+pub fn next_section<'a>(
+    input: &'a str,
+    sections: &mut impl Iterator<Item = &'a str>,
+    expected: &str,
+) -> Result<&'a str, ParseError> {
+    next_line(input, sections, expected)
+}
+
+/// Parses the numbers in text, which are separated by any of separators
+///
+/// Whitespace around the numbers and repeated separators are allowed, so
+/// aligned columns work too.
+pub fn numbers<T: std::str::FromStr>(
+    input: &str,
+    text: &str,
+    separators: &[char],
+) -> Result<Vec<T>, ParseError> {
+    text.split(separators)
+        .map(str::trim)
+        .filter(|num| !num.is_empty())
+        .map(|num| number(input, num))
+        .collect()
+}
+
+/// Splits a `key: values` line into its key and values
+pub fn header<'a>(input: &str, line: &'a str) -> Result<(&'a str, &'a str), ParseError> {
+    let (key, values) = split_once(input, line, ":")?;
+    Ok((key, values.trim_start()))
+}
+
+/// The values of a `key: values` line with the given key
+pub fn header_values<'a>(input: &str, line: &'a str, key: &str) -> Result<&'a str, ParseError> {
+    let (found, values) = header(input, line)?;
+    if found != key {
+        return Err(ParseError::new(input, found, format!("\"{}\"", key)));
+    }
+    Ok(values)
+}
+
+/// Splits an adjacency line like `a -> b, c` into its node and neighbors
+pub fn adjacency<'a>(
+    input: &str,
+    line: &'a str,
+    arrow: &str,
+    separator: &str,
+) -> Result<(&'a str, Vec<&'a str>), ParseError> {
+    let (node, neighbors) = split_once(input, line, arrow)?;
+    if node.trim().is_empty() {
+        return Err(ParseError::new(input, line, "a node"));
+    }
+    let neighbors: Vec<_> = neighbors
+        .split(separator)
+        .map(str::trim)
+        .filter(|neighbor| !neighbor.is_empty())
+        .collect();
+    if neighbors.is_empty() {
+        return Err(ParseError::new(input, line, "some neighbors"));
+    }
+    Ok((node.trim(), neighbors))
+}
+
+/// Parses text like `x,y,z` as N numbers
+///
+/// The numbers are separated as for numbers.
+pub fn tuple<T: std::str::FromStr, const N: usize>(
+    input: &str,
+    text: &str,
+    separator: char,
+) -> Result<[T; N], ParseError> {
+    let values: Vec<T> = numbers(input, text, &[separator])?;
+    values.try_into().map_err(|_| {
+        ParseError::new(
+            input,
+            text,
+            format!("{} numbers separated by '{}'", N, separator),
+        )
+    })
+}
+
+/// Parses text like `x,y,z~x,y,z` as two tuples
+pub fn tuple_pair<T: std::str::FromStr, const N: usize>(
+    input: &str,
+    text: &str,
+    between: &str,
+    separator: char,
+) -> Result<([T; N], [T; N]), ParseError> {
+    let (first, second) = split_once(input, text, between)?;
+    Ok((
+        tuple(input, first, separator)?,
+        tuple(input, second, separator)?,
+    ))
+}
+
+/// Parses input as a Map
+pub fn map<T, C>(input: &str) -> Result<super::world::Map<T, C>, ParseError>
+where
+    T: super::world::LengthType + TryFrom<usize>,
+    C: super::world::Cell + TryFrom<u8>,
+    usize: TryFrom<T>,
+    <usize as TryFrom<T>>::Error: std::fmt::Debug,
+{
+    super::world::Map::try_from_str(input)
+        .map_err(|error| ParseError::from_map_error(input, input, error))
+}
+
+/// Parses input as a Map with a border around it
+pub fn map_with_border<T>(input: &str) -> Result<super::world::Map<T>, ParseError>
+where
+    T: super::world::LengthType + TryFrom<usize>,
+    usize: TryFrom<T>,
+    <usize as TryFrom<T>>::Error: std::fmt::Debug,
+{
+    super::world::Map::try_from_str_with_border(input)
+        .map_err(|error| ParseError::from_map_error(input, input, error))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn errors_have_positions() {
+        let input = "1 2\n3 x 4\n";
+        let x = &input[6..7];
+        let error = number::<u8>(input, x).expect_err("Not a number");
+        assert_eq!((error.line, error.column), (2, 3));
+        assert_eq!(
+            error.to_string(),
+            "Line 2, column 3: expected a number, found \"x\""
+        );
+        let error = split_once(input, &input[4..9], ": ").expect_err("No separator");
+        assert_eq!((error.line, error.column), (2, 1));
+    }
+
+    #[test]
+    fn sections_are_split_at_blank_lines() {
+        let input = "\na\nb\n\n\n\nc\n \nd";
+        assert_eq!(sections(input).collect::<Vec<_>>(), vec!["a\nb", "c", "d"]);
+        let error = ParseError::new(input, sections(input).nth(1).expect("c"), "");
+        assert_eq!((error.line, error.column), (7, 1));
+
+        let input = "seeds: 1\r\n\r\nmap:\r\n1 2 3\r\n";
+        assert_eq!(
+            sections(input).collect::<Vec<_>>(),
+            vec!["seeds: 1", "map:\r\n1 2 3"]
+        );
+        assert_eq!(sections("\n\n").count(), 0);
+    }
+
+    #[test]
+    fn next_line_and_section() {
+        let input = "a\nb\n\nc\n";
+        let mut lines = input.lines();
+        assert_eq!(next_line(input, &mut lines, "a"), Ok("a"));
+        let mut sections = sections(input);
+        assert_eq!(next_section(input, &mut sections, "a"), Ok("a\nb"));
+        assert_eq!(next_section(input, &mut sections, "c"), Ok("c"));
+        let error = next_section(input, &mut sections, "more").expect_err("No more");
+        assert_eq!((error.line, error.column), (5, 1));
+        assert_eq!(error.expected, "more");
+    }
+
+    #[test]
+    fn numbers_allow_repeated_separators() {
+        let input = "7  15   30\n1, -2,3\n4 x\n";
+        let mut lines = input.lines();
+        let line = lines.next().expect("A line");
+        assert_eq!(numbers::<u8>(input, line, &[' ']), Ok(vec![7, 15, 30]));
+        let line = lines.next().expect("A line");
+        assert_eq!(numbers::<i8>(input, line, &[',']), Ok(vec![1, -2, 3]));
+        assert_eq!(numbers::<i8>(input, line, &[',', ' ']), Ok(vec![1, -2, 3]));
+        let line = lines.next().expect("A line");
+        let error = numbers::<u8>(input, line, &[' ']).expect_err("Not a number");
+        assert_eq!((error.line, error.column), (3, 3));
+        assert_eq!(numbers::<u8>(input, "", &[' ']), Ok(vec![]));
+    }
+
+    #[test]
+    fn headers() {
+        let input = "Time:      7  15\nDistance: 9\n";
+        let line = input.lines().next().expect("A line");
+        assert_eq!(header(input, line), Ok(("Time", "7  15")));
+        assert_eq!(header_values(input, line, "Time"), Ok("7  15"));
+        let error = header_values(input, line, "Distance").expect_err("Time");
+        assert_eq!((error.line, error.column), (1, 1));
+        assert_eq!(error.found, "Time");
+        let error = header(input, &input[..4]).expect_err("No colon");
+        assert_eq!(error.expected, "\":\"");
+    }
+
+    #[test]
+    fn adjacency_lines() {
+        let input = "a -> b, c\nd ->\n -> e\n";
+        let mut lines = input.lines();
+        let line = lines.next().expect("A line");
+        assert_eq!(
+            adjacency(input, line, " -> ", ","),
+            Ok(("a", vec!["b", "c"]))
+        );
+        assert_eq!(
+            adjacency(input, line, "->", " "),
+            Ok(("a", vec!["b,", "c"]))
+        );
+        let line = lines.next().expect("A line");
+        let error = adjacency(input, line, "->", ",").expect_err("No neighbors");
+        assert_eq!((error.line, error.expected.as_str()), (2, "some neighbors"));
+        let line = lines.next().expect("A line");
+        let error = adjacency(input, line, "->", ",").expect_err("No node");
+        assert_eq!((error.line, error.expected.as_str()), (3, "a node"));
+    }
+
+    #[test]
+    fn tuples() {
+        let input = "1,0,1~1,2,1\n50 98  2\n1,2\n";
+        let mut lines = input.lines();
+        let line = lines.next().expect("A line");
+        assert_eq!(
+            tuple_pair::<i8, 3>(input, line, "~", ','),
+            Ok(([1, 0, 1], [1, 2, 1]))
+        );
+        let error = tuple::<i8, 2>(input, &line[..5], ',').expect_err("Too many");
+        assert_eq!((error.line, error.column), (1, 1));
+        assert_eq!(error.expected, "2 numbers separated by ','");
+        let error = tuple_pair::<i8, 3>(input, line, "@", ',').expect_err("No @");
+        assert_eq!(error.found, line);
+        // The same rule as numbers, so double spaces work
+        let line = lines.next().expect("A line");
+        assert_eq!(tuple::<u8, 3>(input, line, ' '), Ok([50, 98, 2]));
+        let line = lines.next().expect("A line");
+        let error = tuple::<u8, 3>(input, line, ',').expect_err("Too few");
+        assert_eq!((error.line, error.column), (3, 1));
+    }
+
+    #[test]
+    fn map_errors_are_moved_to_the_map() {
+        let input = "header\n\n..\n.#.\n";
+        let error = map::<i32, u8>(&input[8..]).expect_err("Ragged");
+        assert_eq!(error.line, 2);
+        let error = ParseError::from_map_error(
+            input,
+            &input[8..],
+            MapParseError::RaggedRow {
+                line: 2,
+                expected: 2,
+                found: 3,
+            },
+        );
+        assert_eq!((error.line, error.column), (4, 1));
+        assert_eq!(error.found, "3 characters: .#.");
+    }
+}
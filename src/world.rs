@@ -14,6 +14,7 @@ pub trait LengthType:
     + std::fmt::Debug
     + ToPrimitive
     + std::hash::Hash
+    + num::traits::Euclid
 {
 }
 
@@ -22,58 +23,139 @@ impl LengthType for i32 {}
 impl LengthType for i64 {}
 impl LengthType for i128 {}
 
+/// The type of the values in a Map
+///
+/// Maps that are parsed from, or printed as, text also need the values to
+/// convert from and into bytes.
+pub trait Cell: Copy + Eq + std::fmt::Debug {}
+
+impl<C: Copy + Eq + std::fmt::Debug> Cell for C {}
+
+/// Implements the component wise arithmetic operators for a point type
+macro_rules! impl_point_ops {
+    ($point:ident, $($field:ident),+) => {
+        impl<T: std::ops::Add<Output = T>> std::ops::Add for $point<T> {
+            type Output = Self;
+
+            fn add(self, other: Self) -> Self {
+                Self {
+                    $($field: self.$field + other.$field),+
+                }
+            }
+        }
+
+        impl<T: std::ops::Sub<Output = T>> std::ops::Sub for $point<T> {
+            type Output = Self;
+
+            fn sub(self, other: Self) -> Self {
+                Self {
+                    $($field: self.$field - other.$field),+
+                }
+            }
+        }
+
+        impl<T: std::ops::Neg<Output = T>> std::ops::Neg for $point<T> {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self {
+                    $($field: -self.$field),+
+                }
+            }
+        }
+
+        impl<T: std::ops::Mul<Output = T> + Copy> std::ops::Mul<T> for $point<T> {
+            type Output = Self;
+
+            fn mul(self, factor: T) -> Self {
+                Self {
+                    $($field: self.$field * factor),+
+                }
+            }
+        }
+
+        impl<T: std::ops::AddAssign> std::ops::AddAssign for $point<T> {
+            fn add_assign(&mut self, other: Self) {
+                $(self.$field += other.$field;)+
+            }
+        }
+
+        impl<T: std::ops::SubAssign> std::ops::SubAssign for $point<T> {
+            fn sub_assign(&mut self, other: Self) {
+                $(self.$field -= other.$field;)+
+            }
+        }
+    };
+}
+
+mod cardinal_dir;
+mod connectivity;
+mod cuboid;
 mod dir;
+mod dir3;
+mod dir8;
+mod dir_parse_error;
+mod distance_map;
+pub mod graph;
+mod map_parse_error;
 mod point;
+mod point3;
+mod region;
+pub mod search;
+mod sparse_map;
+mod stencil;
+mod tiled_map;
+mod voxel_map;
+pub use cardinal_dir::CardinalDir;
+pub use connectivity::Connectivity;
+pub use cuboid::Cuboid;
 pub use dir::Dir;
+pub use dir3::Dir3;
+pub use dir8::Dir8;
+pub use dir_parse_error::DirParseError;
+pub use distance_map::DistanceMap;
+use graph::Graph;
+pub use map_parse_error::MapParseError;
 use num::*;
 pub use point::Point;
+pub use point3::Point3;
+pub use region::Region;
+use search::SearchProblem;
+pub use sparse_map::SparseMap;
+pub use stencil::Stencil;
+pub use tiled_map::TiledMap;
+pub use voxel_map::VoxelMap;
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-struct CostAndPoint<T: LengthType, U: Num>(U, Point<T>);
-
-impl<T: LengthType + PartialOrd + Eq + PartialEq, U: Num + Ord + PartialOrd + Eq + PartialEq> Ord
-    for CostAndPoint<T, U>
-{
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other.0.cmp(&self.0)
-    }
-}
-
-impl<T: LengthType + PartialOrd + Eq + PartialEq, U: Num + Ord + PartialOrd + Eq + PartialEq>
-    PartialOrd for CostAndPoint<T, U>
-{
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(other.0.cmp(&self.0))
-    }
-}
+/// Positions with the direction used to get there
+pub type Path<T> = Vec<(Point<T>, Dir)>;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub struct Map<T: LengthType = Length>
+pub struct Map<T: LengthType = Length, C: Cell = u8>
 where
     usize: TryFrom<T>,
     <usize as TryFrom<T>>::Error: std::fmt::Debug,
 {
-    data: Vec<u8>,
+    data: Vec<C>,
     width: T,
     height: T,
     has_border: bool,
 }
 
-pub struct MapIterator<'a, T: LengthType>
+pub struct MapIterator<'a, T: LengthType, C: Cell = u8>
 where
     usize: TryFrom<T>,
     <usize as TryFrom<T>>::Error: std::fmt::Debug,
 {
-    map: &'a Map<T>,
+    map: &'a Map<T, C>,
     pos: Point<T>,
 }
 
-impl<'a, T: LengthType> MapIterator<'a, T>
+impl<'a, T: LengthType, C: Cell> MapIterator<'a, T, C>
 where
     usize: TryFrom<T>,
     <usize as TryFrom<T>>::Error: std::fmt::Debug,
 {
-    pub fn new(map: &'a Map<T>) -> Self {
+    pub fn new(map: &'a Map<T, C>) -> Self {
         Self {
             map,
             pos: Point::<T> {
@@ -84,12 +166,12 @@ where
     }
 }
 
-impl<'a, T: LengthType> Iterator for MapIterator<'a, T>
+impl<'a, T: LengthType, C: Cell> Iterator for MapIterator<'a, T, C>
 where
     usize: TryFrom<T>,
     <usize as TryFrom<T>>::Error: std::fmt::Debug,
 {
-    type Item = (Point<T>, u8);
+    type Item = (Point<T>, C);
 
     fn next(&mut self) -> Option<Self::Item> {
         if self.pos.x >= self.map.get_width() {
@@ -106,22 +188,22 @@ where
     }
 }
 
-pub struct MapNeighborIterator<'a, T: LengthType>
+pub struct MapNeighborIterator<'a, T: LengthType, C: Cell = u8>
 where
     usize: TryFrom<T>,
     <usize as TryFrom<T>>::Error: std::fmt::Debug,
 {
-    map: &'a Map<T>,
+    map: &'a Map<T, C>,
     pos: Point<T>,
     dir: Dir,
 }
 
-impl<'a, T: LengthType> MapNeighborIterator<'a, T>
+impl<'a, T: LengthType, C: Cell> MapNeighborIterator<'a, T, C>
 where
     usize: TryFrom<T>,
     <usize as TryFrom<T>>::Error: std::fmt::Debug,
 {
-    pub fn new(map: &'a Map<T>, pos: Point<T>) -> Self {
+    pub fn new(map: &'a Map<T, C>, pos: Point<T>) -> Self {
         Self {
             map,
             pos,
@@ -130,12 +212,12 @@ where
     }
 }
 
-impl<'a, T: LengthType> Iterator for MapNeighborIterator<'a, T>
+impl<'a, T: LengthType, C: Cell> Iterator for MapNeighborIterator<'a, T, C>
 where
     usize: TryFrom<T>,
     <usize as TryFrom<T>>::Error: std::fmt::Debug,
 {
-    type Item = (Point<T>, Dir, u8);
+    type Item = (Point<T>, Dir, C);
 
     fn next(&mut self) -> Option<Self::Item> {
         loop {
@@ -157,7 +239,7 @@ where
     }
 }
 
-impl<T: LengthType> Map<T>
+impl<T: LengthType, C: Cell> Map<T, C>
 where
     usize: TryFrom<T>,
     <usize as TryFrom<T>>::Error: std::fmt::Debug,
@@ -174,11 +256,11 @@ where
         usize::try_from(pos.x + pos.y * self.width).expect("Positive index")
     }
 
-    pub fn get_at_unchecked(&self, pos: Point<T>) -> u8 {
+    pub fn get_at_unchecked(&self, pos: Point<T>) -> C {
         self.data[self.get_index_for(pos)]
     }
 
-    pub fn get_at(&self, pos: Point<T>) -> Option<u8> {
+    pub fn get_at(&self, pos: Point<T>) -> Option<C> {
         if self.is_inside_map(pos) {
             Some(self.data[self.get_index_for(pos)])
         } else {
@@ -186,18 +268,25 @@ where
         }
     }
 
-    pub fn set_at(&mut self, pos: Point<T>, val: u8) {
+    /// Gets the value at pos as if the map was repeated in all directions
+    pub fn get_wrapped(&self, pos: Point<T>) -> C {
+        self.tiled().get_at(pos)
+    }
+
+    /// A view of the map repeated in all directions
+    pub fn tiled(&self) -> TiledMap<'_, T, C> {
+        TiledMap::new(self)
+    }
+
+    pub fn set_at(&mut self, pos: Point<T>, val: C) {
         let index = self.get_index_for(pos);
         self.data[index] = val
     }
 
-    pub fn new(width: T, height: T) -> Self {
+    pub fn new_filled(width: T, height: T, val: C) -> Self {
         let mut data =
             Vec::with_capacity(usize::try_from(width * height).expect("Positive number"));
-        data.resize_with(
-            usize::try_from(width * height).expect("Positive size"),
-            || b'.',
-        );
+        data.resize(usize::try_from(width * height).expect("Positive size"), val);
         Self {
             data,
             width,
@@ -206,7 +295,7 @@ where
         }
     }
 
-    pub fn add_boarder(&mut self, val: u8) {
+    pub fn add_boarder(&mut self, val: C) {
         for y in range(Zero::zero(), self.get_height()) {
             self.set_at(Point { x: Zero::zero(), y }, val);
             self.set_at(
@@ -229,103 +318,142 @@ where
         }
     }
 
-    pub fn from_string(s: &str) -> Self
+    fn parse_rows(s: &str) -> Result<Vec<&[u8]>, MapParseError> {
+        let rows: Vec<_> = s.lines().map(str::as_bytes).collect();
+        // Blank rows before the first one with cells are ragged, not empty
+        let width = rows
+            .iter()
+            .map(|row| row.len())
+            .find(|&width| width > 0)
+            .ok_or(MapParseError::Empty)?;
+        for (line, row) in rows.iter().enumerate() {
+            if let Some(column) = row.iter().position(|c| !c.is_ascii()) {
+                return Err(MapParseError::NonAscii {
+                    line: line + 1,
+                    column: column + 1,
+                });
+            }
+            if row.len() != width {
+                return Err(MapParseError::RaggedRow {
+                    line: line + 1,
+                    expected: width,
+                    found: row.len(),
+                });
+            }
+        }
+        Ok(rows)
+    }
+
+    fn dimensions(width: usize, height: usize) -> Result<(T, T), MapParseError>
     where
         T: TryFrom<usize>,
-        <T as TryFrom<usize>>::Error: std::fmt::Debug,
     {
-        let height = s.lines().count();
-        let width = s.lines().next().expect("At least one line").len();
-        let mut data = Vec::with_capacity(height * width);
-        for c in s.chars() {
-            if c != '\n' {
-                data.push(u8::try_from(c).expect("Ascii char"));
+        let too_large = || MapParseError::TooLarge { width, height };
+        // All indexes must fit in T too
+        width
+            .checked_mul(height)
+            .and_then(|size| T::try_from(size).ok())
+            .ok_or_else(too_large)?;
+        let width = T::try_from(width).map_err(|_| too_large())?;
+        let height = T::try_from(height).map_err(|_| too_large())?;
+        Ok((width, height))
+    }
+
+    pub fn try_from_str(s: &str) -> Result<Self, MapParseError>
+    where
+        T: TryFrom<usize>,
+        C: TryFrom<u8>,
+    {
+        let rows = Self::parse_rows(s)?;
+        let (width, height) = Self::dimensions(rows[0].len(), rows.len())?;
+        let mut data = Vec::with_capacity(rows.len() * rows[0].len());
+        for (line, row) in rows.iter().enumerate() {
+            for (column, &c) in row.iter().enumerate() {
+                data.push(C::try_from(c).map_err(|_| MapParseError::UnknownCell {
+                    line: line + 1,
+                    column: column + 1,
+                    c: char::from(c),
+                })?);
             }
         }
-
-        let width = T::try_from(width).expect("Positive width");
-        let height = T::try_from(height).expect("Positive height");
-        Self {
+        Ok(Self {
             data,
             width,
             height,
             has_border: false,
-        }
+        })
     }
 
-    pub fn from_string_with_border(s: &str) -> Self
+    pub fn from_string(s: &str) -> Self
     where
         T: TryFrom<usize>,
-        <T as TryFrom<usize>>::Error: std::fmt::Debug,
+        C: TryFrom<u8>,
     {
-        let height = s.lines().count() + 2;
-        let width = s.lines().next().expect("At least one line").len() + 2;
-        let mut data = Vec::with_capacity(height * width);
-        data.push(b'+');
-        for _x in 0..width - 2 {
-            data.push(b'-');
-        }
-        data.push(b'+');
-        data.push(b'|');
-        for c in s.chars() {
-            if c != '\n' {
-                data.push(u8::try_from(c).expect("Ascii char"));
-            } else {
-                data.push(b'|');
-                data.push(b'|');
-            }
-        }
-        data.push(b'|');
-        data.push(b'+');
-        for _x in 0..width - 2 {
-            data.push(b'-');
-        }
-        data.push(b'+');
-
-        let width = T::try_from(width).expect("Positive width");
-        let height = T::try_from(height).expect("Positive height");
-        Self {
-            data,
-            width,
-            height,
-            has_border: true,
-        }
+        Self::try_from_str(s).expect("Valid map")
     }
 
     pub fn print_with_overlay<F>(&self, mut f: F)
     where
-        F: FnMut(Point<T>, u8) -> Option<u8>,
+        F: FnMut(Point<T>, C) -> Option<u8>,
+        C: Into<u8>,
     {
         for y in range(Zero::zero(), self.height) {
             for x in range(Zero::zero(), self.width) {
                 let pos = Point { x, y };
-                let mut c = self.get_at_unchecked(pos);
-                if let Some(new_c) = f(pos, c) {
-                    c = new_c;
-                }
+                let c = self.get_at_unchecked(pos);
+                let c = f(pos, c).unwrap_or_else(|| c.into());
                 print!("{}", char::from(c));
             }
             println!();
         }
     }
 
-    pub fn print(&self) {
+    pub fn print(&self)
+    where
+        C: Into<u8>,
+    {
         self.print_with_overlay(|_, _| None);
     }
 
-    pub fn iter(&self) -> MapIterator<T> {
+    pub fn iter(&self) -> MapIterator<'_, T, C> {
         MapIterator::new(self)
     }
 
-    pub fn neighbors(&self, pos: Point<T>) -> MapNeighborIterator<T> {
+    pub fn neighbors(&self, pos: Point<T>) -> MapNeighborIterator<'_, T, C> {
         MapNeighborIterator::new(self, pos)
     }
 
+    /// The neighbors north, east, south and west of pos that are inside the map
+    pub fn neighbors4(
+        &self,
+        pos: Point<T>,
+    ) -> impl Iterator<Item = (Point<T>, CardinalDir, C)> + '_ {
+        pos.neighbors4()
+            .filter_map(|(pos, dir)| Some((pos, dir, self.get_at(pos)?)))
+    }
+
+    /// The eight neighbors of pos that are inside the map, clockwise from north
+    pub fn neighbors8(&self, pos: Point<T>) -> impl Iterator<Item = (Point<T>, Dir8, C)> + '_ {
+        pos.neighbors8()
+            .filter_map(|(pos, dir)| Some((pos, dir, self.get_at(pos)?)))
+    }
+
+    /// The neighbors of pos given by stencil that are inside the map
+    pub fn neighbors_with<'a>(
+        &'a self,
+        pos: Point<T>,
+        stencil: &'a Stencil<T>,
+    ) -> impl Iterator<Item = (Point<T>, C)> + 'a {
+        stencil
+            .around(pos)
+            .filter_map(|pos| Some((pos, self.get_at(pos)?)))
+    }
+
     pub fn transform_area<F>(&mut self, from: Point<T>, to: Point<T>, mut f: F) -> bool
     where
-        F: FnMut(&Self, Point<T>, u8) -> u8,
+        F: FnMut(&Self, Point<T>, C) -> C,
     {
-        let mut new_map = Map::new(self.width, self.height);
+        let mut new_map = self.clone();
         let mut any_change = false;
         for (pos, c) in self.iter() {
             if pos.x >= from.x && pos.y >= from.y && pos.x < to.x && pos.y < to.y {
@@ -346,7 +474,7 @@ where
 
     pub fn transform<F>(&mut self, f: F) -> bool
     where
-        F: FnMut(&Self, Point<T>, u8) -> u8,
+        F: FnMut(&Self, Point<T>, C) -> C,
     {
         if self.has_border {
             self.transform_area(
@@ -375,6 +503,22 @@ where
         }
     }
 
+    /// Like transform, but f is given the neighbors of each position
+    ///
+    /// The neighbors are those of stencil that are inside the map, with
+    /// their values from before the transform.
+    pub fn transform_with<F>(&mut self, stencil: &Stencil<T>, mut f: F) -> bool
+    where
+        F: FnMut(Point<T>, C, &[(Point<T>, C)]) -> C,
+    {
+        let mut neighbors = Vec::new();
+        self.transform(|map, pos, c| {
+            neighbors.clear();
+            neighbors.extend(map.neighbors_with(pos, stencil));
+            f(pos, c, &neighbors)
+        })
+    }
+
     pub fn is_inside_map(&self, pos: Point<T>) -> bool {
         pos.x >= Zero::zero()
             && pos.y >= Zero::zero()
@@ -388,7 +532,7 @@ where
     /// false
     pub fn walk_until<F>(&self, pos: Point<T>, dir: Dir, mut f: F) -> Point<T>
     where
-        F: FnMut(Point<T>, u8) -> bool,
+        F: FnMut(Point<T>, C) -> bool,
     {
         let mut pos = pos;
         loop {
@@ -403,101 +547,855 @@ where
 
     /// flood fill the map from point pos with val
     /// Only fills north, south, east and west of each position
-    pub fn flood_cardinal(&mut self, pos: Point<T>, empty: u8, val: u8) {
-        if self.get_at_unchecked(pos) != empty {
-            // Nothing to fill here
-            return;
-        }
-        let min_pos = self.walk_until(pos, Dir::West, |_, c| c != empty);
-        let max_pos = self.walk_until(pos, Dir::East, |_, c| c != empty);
-
-        let mut pos = min_pos;
-        while pos.x <= max_pos.x {
-            self.set_at(pos, val);
-            pos = pos.walk(Dir::East);
-        }
-        pos = min_pos;
-        while pos.x <= max_pos.x {
-            pos.y -= One::one();
-            if pos.y > Zero::zero() {
-                self.flood_cardinal(pos, empty, val);
+    pub fn flood_cardinal(&mut self, pos: Point<T>, empty: C, val: C) -> Option<Region<T>> {
+        self.flood_fill(pos, Connectivity::Four, |_, c| (c == empty).then_some(val))
+    }
+
+    pub fn flood_cardinal_with<F>(&mut self, pos: Point<T>, f: &mut F) -> Option<Region<T>>
+    where
+        F: FnMut(Point<T>, C) -> Option<C>,
+    {
+        self.flood_fill(pos, Connectivity::Four, f)
+    }
+
+    /// Fills the region around pos
+    ///
+    /// f returns the new value of a position that is part of the region,
+    /// or None if it isn't. Every position is filled at most once.
+    /// Returns None if pos isn't part of the region.
+    pub fn flood_fill<F>(
+        &mut self,
+        pos: Point<T>,
+        connectivity: Connectivity,
+        f: F,
+    ) -> Option<Region<T>>
+    where
+        F: FnMut(Point<T>, C) -> Option<C>,
+    {
+        self.flood_fill_with(pos, &connectivity.stencil(), f)
+    }
+
+    /// Like flood_fill, but spreads to the neighbors given by stencil
+    ///
+    /// The perimeter and sides of the region are still those of its
+    /// cardinal outline.
+    pub fn flood_fill_with<F>(
+        &mut self,
+        pos: Point<T>,
+        stencil: &Stencil<T>,
+        mut f: F,
+    ) -> Option<Region<T>>
+    where
+        F: FnMut(Point<T>, C) -> Option<C>,
+    {
+        let mut filled = vec![false; self.data.len()];
+        let mut cells = Vec::new();
+        let mut to_fill = vec![pos];
+        while let Some(pos) = to_fill.pop() {
+            let Some(c) = self.get_at(pos) else {
+                continue;
+            };
+            let index = self.get_index_for(pos);
+            if filled[index] {
+                continue;
             }
-            pos.y = pos.y + One::one() + One::one();
-            if pos.y < self.get_height() {
-                self.flood_cardinal(pos, empty, val);
+            if let Some(val) = f(pos, c) {
+                self.set_at(pos, val);
+                filled[index] = true;
+                cells.push(pos);
+                to_fill.extend(stencil.around(pos));
             }
-            pos.y -= One::one();
-            pos = pos.walk(Dir::East);
         }
+        if cells.is_empty() {
+            return None;
+        }
+        Some(Region::from_cells(&cells, self.width, self.height, |pos| {
+            filled[self.get_index_for(pos)]
+        }))
     }
 
-    pub fn flood_cardinal_with<F>(&mut self, pos: Point<T>, f: &mut F)
+    /// Splits the positions where predicate is true into connected components
+    ///
+    /// Returns a map with the id of every position's component, where 0 is
+    /// used for positions that aren't part of any, and the summary of every
+    /// component. The component with id n is at index n - 1.
+    pub fn components<F>(
+        &self,
+        mut predicate: F,
+        connectivity: Connectivity,
+    ) -> (Map<T, u32>, Vec<Region<T>>)
     where
-        F: FnMut(Point<T>, u8) -> Option<u8>,
+        F: FnMut(Point<T>, C) -> bool,
     {
-        if f(pos, self.get_at_unchecked(pos)).is_none() {
-            // Nothing to fill here
-            return;
-        }
-        let min_pos = self.walk_until(pos, Dir::West, |pos, c| f(pos, c).is_none());
-        let max_pos = self.walk_until(pos, Dir::East, |pos, c| f(pos, c).is_none());
-
-        let mut pos = min_pos;
-        while pos.x <= max_pos.x {
-            let val = f(pos, self.get_at_unchecked(pos)).expect("value");
-            self.set_at(pos, val);
-            pos = pos.walk(Dir::East);
-        }
-        pos = min_pos;
-        while pos.x <= max_pos.x {
-            pos.y -= One::one();
-            if pos.y >= Zero::zero() {
-                self.flood_cardinal_with(pos, f);
+        let stencil = connectivity.stencil();
+        let included: Vec<_> = self.iter().map(|(pos, c)| predicate(pos, c)).collect();
+        let mut labels = Map::new_filled(self.width, self.height, 0);
+        let mut regions = Vec::new();
+        for (index, (start, _)) in self.iter().enumerate() {
+            if !included[index] || labels.data[index] != 0 {
+                continue;
             }
-            pos.y = pos.y + One::one() + One::one();
-            if pos.y < self.get_height() {
-                self.flood_cardinal_with(pos, f);
+            let label = u32::try_from(regions.len() + 1).expect("Not too many components");
+            labels.data[index] = label;
+            let mut cells = Vec::new();
+            let mut to_visit = vec![start];
+            while let Some(pos) = to_visit.pop() {
+                cells.push(pos);
+                for new_pos in stencil.around(pos) {
+                    if self.is_inside_map(new_pos) {
+                        let index = self.get_index_for(new_pos);
+                        if included[index] && labels.data[index] == 0 {
+                            labels.data[index] = label;
+                            to_visit.push(new_pos);
+                        }
+                    }
+                }
             }
-            pos.y -= One::one();
-            pos = pos.walk(Dir::East);
+            regions.push(Region::from_cells(&cells, self.width, self.height, |pos| {
+                labels.get_at_unchecked(pos) == label
+            }));
         }
+        (labels, regions)
     }
 
-    pub fn find(&self, needle: u8) -> Vec<Point<T>> {
+    /// Compresses the walkable positions into a graph between nodes
+    ///
+    /// The nodes are the walkable positions where is_node is true. The edges
+    /// follow corridors of walkable positions from node to node and weigh
+    /// the fewest steps, also through open areas. Each direction out of a
+    /// node gives at most one edge to each other node. edge_rule tells if a
+    /// corridor may leave a position in a direction, to allow one way
+    /// corridors.
+    /// Corridors that end without reaching a node are left out.
+    pub fn compress_to_graph<W, N, E>(
+        &self,
+        mut is_walkable: W,
+        mut is_node: N,
+        mut edge_rule: E,
+    ) -> Graph<Point<T>>
+    where
+        W: FnMut(Point<T>, C) -> bool,
+        N: FnMut(Point<T>, C) -> bool,
+        E: FnMut(Point<T>, CardinalDir, C) -> bool,
+    {
+        let mut graph = Graph::new();
+        for (pos, c) in self.iter() {
+            if is_walkable(pos, c) && is_node(pos, c) {
+                graph.add_node(pos);
+            }
+        }
+        for from in 0..graph.len() {
+            let start = *graph.node(from);
+            let start_c = self.get_at_unchecked(start);
+            for (first, dir, first_c) in self.neighbors4(start) {
+                if !is_walkable(first, first_c) || !edge_rule(start, dir, start_c) {
+                    continue;
+                }
+                // Every corridor leaving start is walked on its own, so
+                // corridors that end at the same node each get their edge.
+                // The walk is breadth first, so open areas give the
+                // shortest number of steps to each node.
+                let mut visited = hashbrown::HashSet::new();
+                visited.insert(first);
+                let mut frontier = std::collections::VecDeque::from([(first, 1)]);
+                while let Some((pos, steps)) = frontier.pop_front() {
+                    if let Some(to) = graph.id(&pos) {
+                        graph.add_edge(from, to, steps);
+                        continue;
+                    }
+                    let c = self.get_at_unchecked(pos);
+                    for (new_pos, dir, new_c) in self.neighbors4(pos) {
+                        if new_pos != start
+                            && !visited.contains(&new_pos)
+                            && is_walkable(new_pos, new_c)
+                            && edge_rule(pos, dir, c)
+                        {
+                            visited.insert(new_pos);
+                            frontier.push_back((new_pos, steps + 1));
+                        }
+                    }
+                }
+            }
+        }
+        graph
+    }
+
+    pub fn find(&self, needle: C) -> Vec<Point<T>> {
         self.iter()
             .filter_map(|(p, c)| if c == needle { Some(p) } else { None })
             .collect()
     }
 
-    pub fn bfs<F, U>(&self, from: Point<T>, to: Point<T>, f: &mut F) -> U
+    /// Finds the number of steps from the closest of the from positions
+    /// to every position of the map.
+    ///
+    /// Only moves north, south, east and west and only to positions where
+    /// passable returns true. The from positions are always reachable.
+    pub fn distance_field<F>(&self, from: &[Point<T>], mut passable: F) -> DistanceMap<T>
+    where
+        F: FnMut(Point<T>, C) -> bool,
+    {
+        let mut distances = DistanceMap::new(self.width, self.height);
+        let mut to_expand = std::collections::VecDeque::new();
+        for &pos in from {
+            if self.is_inside_map(pos) && distances.get(pos).is_none() {
+                distances.set(pos, 0);
+                to_expand.push_back(pos);
+            }
+        }
+        while let Some(pos) = to_expand.pop_front() {
+            let distance = distances
+                .get(pos)
+                .expect("Expanded position has a distance")
+                + 1;
+            for (new_pos, _, c) in self.neighbors4(pos) {
+                if distances.get(new_pos).is_none() && passable(new_pos, c) {
+                    distances.set(new_pos, distance);
+                    to_expand.push_back(new_pos);
+                }
+            }
+        }
+        distances
+    }
+
+    /// Finds the lowest cost to move from from to to.
+    ///
+    /// f returns the cost of moving to a neighbor or None if it can't be moved to.
+    /// Returns None if to can't be reached.
+    pub fn bfs<F, U>(&self, from: Point<T>, to: Point<T>, f: &mut F) -> Option<U>
+    where
+        F: FnMut(&Self, Point<T>, Dir, C) -> Option<U>,
+        U: Num + Ord + Copy + std::fmt::Debug,
+    {
+        self.bfs_with(&Stencil::eight(), from, to, &mut |map, pos, step, c| {
+            f(map, pos, step_dir(step), c)
+        })
+    }
+
+    /// Finds the lowest cost path from from to to.
+    ///
+    /// f returns the cost of moving to a neighbor or None if it can't be moved to.
+    /// The path starts with from, with Dir::None, and then has every
+    /// position with the direction that was used to get there.
+    /// Returns None if to can't be reached.
+    pub fn bfs_path<F, U>(&self, from: Point<T>, to: Point<T>, f: &mut F) -> Option<(U, Path<T>)>
+    where
+        F: FnMut(&Self, Point<T>, Dir, C) -> Option<U>,
+        U: Num + Ord + Copy + std::fmt::Debug,
+    {
+        let (cost, path) =
+            self.bfs_path_with(&Stencil::eight(), from, to, &mut |map, pos, step, c| {
+                f(map, pos, step_dir(step), c)
+            })?;
+        let mut result = Vec::with_capacity(path.len());
+        result.push((from, Dir::None));
+        for (&prev, &pos) in path.iter().zip(path.iter().skip(1)) {
+            result.push((pos, step_dir(pos - prev)));
+        }
+        Some((cost, result))
+    }
+
+    /// Like bfs, but moves to the neighbors given by stencil
+    ///
+    /// f is called with the offset from the stencil instead of a direction.
+    pub fn bfs_with<F, U>(
+        &self,
+        stencil: &Stencil<T>,
+        from: Point<T>,
+        to: Point<T>,
+        f: &mut F,
+    ) -> Option<U>
     where
-        F: FnMut(&Self, Point<T>, Dir, u8) -> Option<U>,
+        F: FnMut(&Self, Point<T>, Point<T>, C) -> Option<U>,
         U: Num + Ord + Copy + std::fmt::Debug,
     {
-        // TODO: Give path instead?
-        let mut expanded = hashbrown::HashMap::new();
-        let mut to_expand = std::collections::BinaryHeap::new();
-        to_expand.push(CostAndPoint(Zero::zero(), from));
-        while let Some(CostAndPoint(cost, pos)) = to_expand.pop() {
-            if to == pos {
-                return cost;
+        let problem = MapSearch {
+            map: self,
+            stencil,
+            from,
+            to,
+            f: std::cell::RefCell::new(f),
+        };
+        search::search(&problem).map(|(cost, _)| cost)
+    }
+
+    /// Like bfs_path, but moves to the neighbors given by stencil
+    ///
+    /// The path has every position from from to to.
+    pub fn bfs_path_with<F, U>(
+        &self,
+        stencil: &Stencil<T>,
+        from: Point<T>,
+        to: Point<T>,
+        f: &mut F,
+    ) -> Option<(U, Vec<Point<T>>)>
+    where
+        F: FnMut(&Self, Point<T>, Point<T>, C) -> Option<U>,
+        U: Num + Ord + Copy + std::fmt::Debug,
+    {
+        let problem = MapSearch {
+            map: self,
+            stencil,
+            from,
+            to,
+            f: std::cell::RefCell::new(f),
+        };
+        search::search_path(&problem)
+    }
+}
+
+/// The direction of a step to one of the eight neighbors
+fn step_dir<T: LengthType>(step: Point<T>) -> Dir {
+    let origin = Point {
+        x: T::zero(),
+        y: T::zero(),
+    };
+    origin.direction_to(step).expect("A step to a neighbor")
+}
+
+impl<T: LengthType> Map<T, u8>
+where
+    usize: TryFrom<T>,
+    <usize as TryFrom<T>>::Error: std::fmt::Debug,
+{
+    pub fn new(width: T, height: T) -> Self {
+        Self::new_filled(width, height, b'.')
+    }
+
+    pub fn try_from_str_with_border(s: &str) -> Result<Self, MapParseError>
+    where
+        T: TryFrom<usize>,
+    {
+        let rows = Self::parse_rows(s)?;
+        let width = rows[0].len() + 2;
+        let height = rows.len() + 2;
+        // Check the size before allocating it
+        let (map_width, map_height) = Self::dimensions(width, height)?;
+        let mut data = Vec::with_capacity(height * width);
+        data.push(b'+');
+        data.resize(width - 1, b'-');
+        data.push(b'+');
+        for row in rows {
+            data.push(b'|');
+            data.extend_from_slice(row);
+            data.push(b'|');
+        }
+        data.push(b'+');
+        data.resize(data.len() + width - 2, b'-');
+        data.push(b'+');
+
+        Ok(Self {
+            data,
+            width: map_width,
+            height: map_height,
+            has_border: true,
+        })
+    }
+
+    pub fn from_string_with_border(s: &str) -> Self
+    where
+        T: TryFrom<usize>,
+    {
+        Self::try_from_str_with_border(s).expect("Valid map")
+    }
+}
+
+impl<T: LengthType + TryFrom<usize>, C: Cell + TryFrom<u8>> std::str::FromStr for Map<T, C>
+where
+    usize: TryFrom<T>,
+    <usize as TryFrom<T>>::Error: std::fmt::Debug,
+{
+    type Err = MapParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from_str(s)
+    }
+}
+
+struct MapSearch<'a, T: LengthType, C: Cell, F>
+where
+    usize: TryFrom<T>,
+    <usize as TryFrom<T>>::Error: std::fmt::Debug,
+{
+    map: &'a Map<T, C>,
+    stencil: &'a Stencil<T>,
+    from: Point<T>,
+    to: Point<T>,
+    f: std::cell::RefCell<&'a mut F>,
+}
+
+impl<'a, T: LengthType, C: Cell, F, U> SearchProblem for MapSearch<'a, T, C, F>
+where
+    usize: TryFrom<T>,
+    <usize as TryFrom<T>>::Error: std::fmt::Debug,
+    F: FnMut(&Map<T, C>, Point<T>, Point<T>, C) -> Option<U>,
+    U: Num + Ord + Copy,
+{
+    type State = Point<T>;
+    type Cost = U;
+
+    fn start_states(&self) -> Vec<Self::State> {
+        vec![self.from]
+    }
+
+    fn successors(&self, pos: &Self::State, successors: &mut Vec<(Self::State, Self::Cost)>) {
+        let f = &mut *self.f.borrow_mut();
+        successors.extend(self.stencil.offsets().iter().filter_map(|&step| {
+            let new_pos = *pos + step;
+            let c = self.map.get_at(new_pos)?;
+            f(self.map, new_pos, step, c).map(|cost| (new_pos, cost))
+        }));
+    }
+
+    fn is_goal(&self, pos: &Self::State) -> bool {
+        *pos == self.to
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn walk_on_dots(_: &Map, _: Point, _: Dir, c: u8) -> Option<u32> {
+        (c == b'.').then_some(1)
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(Map::<i32>::try_from_str(""), Err(MapParseError::Empty));
+        assert_eq!(Map::<i32>::try_from_str("\n\n"), Err(MapParseError::Empty));
+        assert_eq!(
+            Map::<i32>::try_from_str("\n#.\n#."),
+            Err(MapParseError::RaggedRow {
+                line: 1,
+                expected: 2,
+                found: 0
+            })
+        );
+        assert_eq!(
+            Map::<i32>::try_from_str_with_border("#.\n\n#."),
+            Err(MapParseError::RaggedRow {
+                line: 2,
+                expected: 2,
+                found: 0
+            })
+        );
+        assert_eq!(
+            Map::<i32>::try_from_str("...\n...\n..\n"),
+            Err(MapParseError::RaggedRow {
+                line: 3,
+                expected: 3,
+                found: 2
+            })
+        );
+        assert_eq!(
+            Map::<i32>::try_from_str_with_border("..\n.é\n"),
+            Err(MapParseError::NonAscii { line: 2, column: 2 })
+        );
+    }
+
+    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    enum Tile {
+        Open,
+        Wall,
+    }
+
+    impl TryFrom<u8> for Tile {
+        type Error = u8;
+
+        fn try_from(c: u8) -> Result<Self, Self::Error> {
+            match c {
+                b'.' => Ok(Tile::Open),
+                b'#' => Ok(Tile::Wall),
+                _ => Err(c),
             }
-            if let Some(old_cost) = expanded.get_mut(&pos) {
-                if *old_cost <= cost {
-                    continue;
+        }
+    }
+
+    #[test]
+    fn typed_cells() {
+        let map = Map::<i32, Tile>::try_from_str(".#\n#.\n").expect("Valid tiles");
+        assert_eq!(map.get_at(Point { x: 1, y: 0 }), Some(Tile::Wall));
+        assert_eq!(map.get_at(Point { x: 1, y: 1 }), Some(Tile::Open));
+        assert_eq!(
+            Map::<i32, Tile>::try_from_str(".#.\n#.#\n..x\n"),
+            Err(MapParseError::UnknownCell {
+                line: 3,
+                column: 3,
+                c: 'x'
+            })
+        );
+    }
+
+    #[test]
+    fn transform_area_keeps_the_rest() {
+        let mut map = Map::<i32>::from_string("....\n.#..\n....\n");
+        // Cells next to a wall become walls, judged on the map before the change,
+        // so (2, 0) stays open although (1, 0) turns into a wall
+        let changed =
+            map.transform_area(Point { x: 1, y: 0 }, Point { x: 3, y: 2 }, |map, pos, c| {
+                if pos
+                    .neighbors4()
+                    .any(|(pos, _)| map.get_at(pos) == Some(b'#'))
+                {
+                    b'#'
                 } else {
-                    *old_cost = cost;
+                    c
                 }
-            } else {
-                expanded.insert(pos, cost);
+            });
+        assert!(changed);
+        let mut expected = Map::<i32>::from_string(".#..\n.##.\n....\n");
+        assert_eq!(map, expected);
+        assert!(!expected.transform_area(Point { x: 0, y: 0 }, Point { x: 4, y: 3 }, |_, _, c| c));
+    }
+
+    #[test]
+    fn stencil_neighbors_at_edges() {
+        let map = Map::<i32>::from_string("abc\ndef\nghi\n");
+        let knight = Stencil::knight();
+        let mut corner: Vec<_> = map.neighbors_with(Point { x: 0, y: 0 }, &knight).collect();
+        corner.sort();
+        assert_eq!(
+            corner,
+            vec![(Point { x: 1, y: 2 }, b'h'), (Point { x: 2, y: 1 }, b'f')]
+        );
+        assert_eq!(map.neighbors_with(Point { x: 1, y: 1 }, &knight).count(), 0);
+
+        let hex = Stencil::hex();
+        let corner: Vec<_> = map.neighbors_with(Point { x: 0, y: 0 }, &hex).collect();
+        assert_eq!(
+            corner,
+            vec![(Point { x: 1, y: 0 }, b'b'), (Point { x: 0, y: 1 }, b'd')]
+        );
+        let edge: Vec<_> = map.neighbors_with(Point { x: 0, y: 2 }, &hex).collect();
+        assert_eq!(
+            edge,
+            vec![
+                (Point { x: 1, y: 2 }, b'h'),
+                (Point { x: 1, y: 1 }, b'e'),
+                (Point { x: 0, y: 1 }, b'd'),
+            ]
+        );
+    }
+
+    #[test]
+    fn stencil_searches() {
+        let mut map = Map::<i32>::new(8, 8);
+        let knight = Stencil::knight();
+        let mut open = |_: &Map, _, _, c| (c == b'.').then_some(1);
+        let from = Point { x: 0, y: 0 };
+        let to = Point { x: 7, y: 7 };
+        assert_eq!(map.bfs_with(&knight, from, to, &mut open), Some(6));
+        let (cost, path) = map
+            .bfs_path_with(&knight, from, to, &mut open)
+            .expect("A path");
+        assert_eq!(cost, 6);
+        assert_eq!(path.len(), 7);
+        assert_eq!(path.first(), Some(&from));
+        assert_eq!(path.last(), Some(&to));
+        for (&a, &b) in path.iter().zip(path.iter().skip(1)) {
+            assert!(knight.offsets().contains(&(b - a)));
+        }
+
+        // A knight can't leave the corner when both of its moves are blocked
+        map.set_at(Point { x: 1, y: 2 }, b'#');
+        map.set_at(Point { x: 2, y: 1 }, b'#');
+        assert_eq!(map.bfs_with(&knight, from, to, &mut open), None);
+        assert_eq!(map.bfs_path_with(&knight, from, to, &mut open), None);
+
+        // Hex neighbors cross one diagonal, but not the other
+        let map = Map::<i32>::from_string(".#\n#.\n");
+        let hex = Stencil::hex();
+        let to = Point { x: 1, y: 1 };
+        assert_eq!(map.bfs_with(&hex, from, to, &mut open), None);
+        let map = Map::<i32>::from_string("#.\n.#\n");
+        let path = map.bfs_path_with(&hex, Point { x: 1, y: 0 }, Point { x: 0, y: 1 }, &mut open);
+        assert_eq!(
+            path,
+            Some((1, vec![Point { x: 1, y: 0 }, Point { x: 0, y: 1 }]))
+        );
+    }
+
+    #[test]
+    fn stencil_flood_and_transform() {
+        let mut map = Map::<i32>::from_string("#.\n.#\n");
+        let region = map
+            .flood_fill_with(Point { x: 1, y: 0 }, &Stencil::hex(), |_, c| {
+                (c == b'.').then_some(b'o')
+            })
+            .expect("Open start");
+        assert_eq!(region.area, 2);
+        assert_eq!(map, Map::<i32>::from_string("#o\no#\n"));
+
+        // A blinker in the game of life
+        let mut map = Map::<i32>::from_string(".....\n..#..\n..#..\n..#..\n.....\n");
+        let life = |_, c, neighbors: &[(Point, u8)]| match (
+            c,
+            neighbors.iter().filter(|(_, c)| *c == b'#').count(),
+        ) {
+            (b'#', 2 | 3) | (_, 3) => b'#',
+            _ => b'.',
+        };
+        assert!(map.transform_with(&Stencil::eight(), life));
+        assert_eq!(
+            map,
+            Map::<i32>::from_string(".....\n.....\n.###.\n.....\n.....\n")
+        );
+        assert!(map.transform_with(&Stencil::eight(), life));
+        assert!(map.transform_with(&Stencil::eight(), life));
+        assert_eq!(
+            map,
+            Map::<i32>::from_string(".....\n.....\n.###.\n.....\n.....\n")
+        );
+    }
+
+    #[test]
+    fn flood_large_open_map() {
+        // Deep enough to overflow the stack of a recursive fill
+        let mut map = Map::<i32>::new(2000, 2000);
+        let region = map
+            .flood_cardinal(Point { x: 1999, y: 1999 }, b'.', b'o')
+            .expect("Open start");
+        assert_eq!(region.area, 2000 * 2000);
+        assert_eq!(region.min, Point { x: 0, y: 0 });
+        assert_eq!(region.max, Point { x: 1999, y: 1999 });
+        assert_eq!(region.perimeter, 4 * 2000);
+        assert!(map.iter().all(|(_, c)| c == b'o'));
+    }
+
+    #[test]
+    fn flood_from_the_first_row_and_column() {
+        let mut map = Map::<i32>::from_string("..#\n.##\n#..\n");
+        let region = map
+            .flood_cardinal(Point { x: 0, y: 0 }, b'.', b'o')
+            .expect("Open start");
+        assert_eq!(region.area, 3);
+        assert!(region.touches_edge);
+        assert_eq!(map, Map::<i32>::from_string("oo#\no##\n#..\n"));
+    }
+
+    #[test]
+    fn flood_connectivity() {
+        let map = Map::<i32>::from_string(".#.\n#.#\n.#.\n");
+        let open = |_, c| (c == b'.').then_some(b'o');
+        let mut four = map.clone();
+        let region = four
+            .flood_fill(Point { x: 1, y: 1 }, Connectivity::Four, open)
+            .expect("Open start");
+        assert_eq!(region.area, 1);
+        assert!(!region.touches_edge);
+        assert_eq!(four.find(b'o'), vec![Point { x: 1, y: 1 }]);
+        let mut eight = map.clone();
+        let region = eight
+            .flood_fill(Point { x: 1, y: 1 }, Connectivity::Eight, open)
+            .expect("Open start");
+        assert_eq!(region.area, 5);
+        assert_eq!(eight, Map::<i32>::from_string("o#o\n#o#\no#o\n"));
+    }
+
+    #[test]
+    fn flood_blocked_start() {
+        let mut map = Map::<i32>::from_string(".#.\n");
+        assert_eq!(map.flood_cardinal(Point { x: 1, y: 0 }, b'.', b'o'), None);
+        assert_eq!(map.flood_cardinal(Point { x: -1, y: 0 }, b'.', b'o'), None);
+        assert_eq!(map, Map::<i32>::from_string(".#.\n"));
+    }
+
+    #[test]
+    fn components_with_holes() {
+        let map = Map::<i32>::from_string("..#..\n.#.#.\n..#..\n");
+        let (labels, regions) = map.components(|_, c| c == b'.', Connectivity::Four);
+        let labels: Vec<_> = labels.iter().map(|(_, label)| label).collect();
+        assert_eq!(labels, vec![1, 1, 0, 2, 2, 1, 0, 3, 0, 2, 1, 1, 0, 2, 2]);
+        assert_eq!(regions.len(), 3);
+        assert_eq!(
+            regions[0],
+            Region {
+                area: 5,
+                min: Point { x: 0, y: 0 },
+                max: Point { x: 1, y: 2 },
+                perimeter: 12,
+                sides: 8,
+                touches_edge: true,
             }
-            for (new_cost, pos) in self
-                .neighbors(pos)
-                .filter_map(|(pos, dir, c)| f(self, pos, dir, c).map(|step| (step + cost, pos)))
-            {
-                to_expand.push(CostAndPoint(new_cost, pos));
+        );
+        assert_eq!(regions[1].area, 5);
+        assert_eq!(regions[1].sides, 8);
+        assert_eq!(
+            regions[2],
+            Region {
+                area: 1,
+                min: Point { x: 2, y: 1 },
+                max: Point { x: 2, y: 1 },
+                perimeter: 4,
+                sides: 4,
+                touches_edge: false,
             }
+        );
+
+        let (labels, regions) = map.components(|_, c| c == b'.', Connectivity::Eight);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].area, 11);
+        assert_eq!(labels.find(1).len(), 11);
+        assert_eq!(labels.find(0), map.find(b'#'));
+
+        let (_, walls) = map.components(|_, c| c == b'#', Connectivity::Four);
+        assert_eq!(walls.len(), 4);
+        assert!(walls
+            .iter()
+            .all(|region| region.area == 1 && region.sides == 4));
+    }
+
+    fn sides_of(map: &str, plant: u8) -> Vec<(usize, usize)> {
+        let map = Map::<i32>::from_string(map);
+        let (_, regions) = map.components(|_, c| c == plant, Connectivity::Four);
+        regions
+            .iter()
+            .map(|region| (region.area, region.sides))
+            .collect()
+    }
+
+    #[test]
+    fn region_sides() {
+        assert_eq!(sides_of("#.\n#.\n##\n", b'#'), vec![(4, 6)]);
+        assert_eq!(
+            sides_of("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE\n", b'E'),
+            vec![(17, 12)]
+        );
+        assert_eq!(
+            sides_of("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE\n", b'X'),
+            vec![(4, 4), (4, 4)]
+        );
+        let holes = "AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA\n";
+        assert_eq!(sides_of(holes, b'A'), vec![(28, 12)]);
+        assert_eq!(sides_of(holes, b'B'), vec![(4, 4), (4, 4)]);
+        assert_eq!(
+            sides_of("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO\n", b'O'),
+            vec![(21, 20)]
+        );
+    }
+
+    #[test]
+    fn compress_open_areas() {
+        let nodes = |map: &Map| {
+            map.compress_to_graph(
+                |_, c| c != b'#',
+                |_, c| c.is_ascii_uppercase(),
+                |_, _, _| true,
+            )
+        };
+        // An open 2x2 area next to A
+        let map = Map::<i32>::from_string("######\n#A.###\n#...B#\n######\n");
+        let graph = nodes(&map);
+        assert_eq!(graph.edges(0), &[(1, 4), (1, 4)]);
+        assert_eq!(graph.edges(1), &[(0, 4)]);
+
+        // Every way out of A is as short as the shortest route through the area
+        let map = Map::<i32>::from_string("######\n#A...#\n#....#\n#....#\n#...B#\n######\n");
+        let graph = nodes(&map);
+        assert_eq!(graph.edges(0), &[(1, 6), (1, 6)]);
+        assert_eq!(graph.edges(1), &[(0, 6), (0, 6)]);
+        assert_eq!(graph.longest_path(0, 1), Some(6));
+    }
+
+    #[test]
+    fn compress_parallel_corridors() {
+        let map =
+            Map::<i32>::from_string("######\n#....#\n#A##B#\n#.##.#\n#.##.#\n#....#\n######\n");
+        let graph = map.compress_to_graph(
+            |_, c| c != b'#',
+            |_, c| c.is_ascii_uppercase(),
+            |_, _, _| true,
+        );
+        assert_eq!(graph.nodes(), &[Point { x: 1, y: 2 }, Point { x: 4, y: 2 }]);
+        for (from, to) in [(0, 1), (1, 0)] {
+            let mut edges = graph.edges(from).to_vec();
+            edges.sort();
+            assert_eq!(edges, vec![(to, 5), (to, 9)]);
+        }
+        assert_eq!(graph.longest_path(0, 1), Some(9));
+
+        // One way corridors only give the edges in their direction
+        let one_way = map.compress_to_graph(
+            |_, c| c != b'#',
+            |_, c| c.is_ascii_uppercase(),
+            |pos, dir, _| pos.y > 2 || dir != CardinalDir::North,
+        );
+        assert_eq!(one_way.edges(0), &[(1, 9)]);
+        assert_eq!(one_way.edges(1), &[(0, 9)]);
+    }
+
+    #[test]
+    fn too_large_maps() {
+        let row = ".".repeat(180);
+        let map: Vec<_> = (0..180).map(|_| row.as_str()).collect();
+        let map = map.join("\n");
+        assert!(Map::<i16>::try_from_str(&map).is_ok());
+        // The border makes it 182x182, more than i16 can index
+        assert_eq!(
+            Map::<i16>::try_from_str_with_border(&map),
+            Err(MapParseError::TooLarge {
+                width: 182,
+                height: 182
+            })
+        );
+        assert!(Map::<i32>::try_from_str_with_border(&map).is_ok());
+    }
+
+    #[test]
+    fn wrapped_positions() {
+        // Width 3, height 2
+        let map = Map::<i32>::from_string("abc\ndef\n");
+        let at = |x, y| map.get_wrapped(Point { x, y });
+        assert_eq!(at(-1, 0), b'c');
+        assert_eq!(at(-4, 0), b'c');
+        assert_eq!(at(0, -1), b'd');
+        assert_eq!(at(-4, -3), b'f');
+        assert_eq!(at(3, 2), b'a');
+        assert_eq!(at(-3, 0), b'a');
+
+        let tiled = map.tiled();
+        let pos = Point { x: -4, y: -1 };
+        assert_eq!(tiled.tile_of(pos), Point { x: -2, y: -1 });
+        assert_eq!(tiled.local_point(pos), Point { x: 2, y: 1 });
+        assert_eq!(
+            tiled.global_point(tiled.tile_of(pos), tiled.local_point(pos)),
+            pos
+        );
+
+        // Clockwise from north, wrapping around the tile edges
+        let neighbors: Vec<_> = tiled.neighbors(Point { x: 0, y: 0 }).collect();
+        assert_eq!(neighbors.len(), 8);
+        assert_eq!(neighbors[0], (Point { x: 0, y: -1 }, Dir::North, b'd'));
+        assert_eq!(neighbors[1], (Point { x: 1, y: -1 }, Dir::NorthEast, b'e'));
+        assert_eq!(neighbors[7], (Point { x: -1, y: -1 }, Dir::NorthWest, b'f'));
+    }
+
+    #[test]
+    fn bfs_path_steps() {
+        let map = Map::<i32>::from_string("..#\n.#.\n...\n");
+        let from = Point { x: 0, y: 0 };
+        let to = Point { x: 2, y: 1 };
+        let (cost, path) = map
+            .bfs_path(from, to, &mut walk_on_dots)
+            .expect("A path around the wall");
+        assert_eq!(Some(cost), map.bfs(from, to, &mut walk_on_dots));
+        assert_eq!(path.len(), usize::try_from(cost).expect("Small cost") + 1);
+        assert_eq!(path[0], (from, Dir::None));
+        assert_eq!(path.last().map(|&(pos, _)| pos), Some(to));
+        for pair in path.windows(2) {
+            let ((prev, _), (pos, dir)) = (pair[0], pair[1]);
+            assert_eq!(prev.walk(dir), pos);
         }
-        Zero::zero()
+    }
+
+    #[test]
+    fn bfs_unreachable() {
+        let map = Map::<i32>::from_string(".#.\n##.\n...\n");
+        let from = Point { x: 0, y: 0 };
+        let to = Point { x: 2, y: 2 };
+        assert_eq!(map.bfs_path(from, to, &mut walk_on_dots), None);
+        assert_eq!(map.bfs(from, to, &mut walk_on_dots), None);
+        assert_eq!(map.bfs(from, from, &mut walk_on_dots), Some(0));
     }
 }
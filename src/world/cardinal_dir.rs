@@ -0,0 +1,187 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::{Dir, Dir8, DirParseError};
+
+/// One of the four directions along the axes
+#[derive(Hash, Eq, PartialEq, Copy, Clone, Debug)]
+pub enum CardinalDir {
+    North,
+    East,
+    South,
+    West,
+}
+
+use CardinalDir::*;
+
+impl CardinalDir {
+    /// In clockwise order
+    const ALL: [Self; 4] = [North, East, South, West];
+
+    pub fn all() -> impl Iterator<Item = Self> {
+        Self::ALL.into_iter()
+    }
+
+    /// Turns 90 degrees quarter_turns times, clockwise for positive numbers
+    pub fn rotate(self, quarter_turns: i32) -> Self {
+        let turns = usize::try_from(quarter_turns.rem_euclid(4)).expect("Non negative number");
+        Self::ALL[(self as usize + turns) % 4]
+    }
+
+    pub fn turn_right(self) -> Self {
+        self.rotate(1)
+    }
+
+    pub fn turn_left(self) -> Self {
+        self.rotate(-1)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(2)
+    }
+
+    /// The direction after a U-turn, same as opposite
+    pub fn turn_around(self) -> Self {
+        self.opposite()
+    }
+}
+
+impl std::fmt::Display for CardinalDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Dir::from(*self).fmt(f)
+    }
+}
+
+impl From<CardinalDir> for Dir {
+    fn from(dir: CardinalDir) -> Self {
+        match dir {
+            North => Dir::North,
+            East => Dir::East,
+            South => Dir::South,
+            West => Dir::West,
+        }
+    }
+}
+
+impl From<CardinalDir> for Dir8 {
+    fn from(dir: CardinalDir) -> Self {
+        match dir {
+            North => Dir8::North,
+            East => Dir8::East,
+            South => Dir8::South,
+            West => Dir8::West,
+        }
+    }
+}
+
+impl TryFrom<Dir8> for CardinalDir {
+    /// The diagonal direction
+    type Error = Dir8;
+
+    fn try_from(dir: Dir8) -> Result<Self, Self::Error> {
+        match dir {
+            Dir8::North => Ok(North),
+            Dir8::East => Ok(East),
+            Dir8::South => Ok(South),
+            Dir8::West => Ok(West),
+            _ => Err(dir),
+        }
+    }
+}
+
+impl TryFrom<Dir> for CardinalDir {
+    /// The diagonal direction, or Dir::None
+    type Error = Dir;
+
+    fn try_from(dir: Dir) -> Result<Self, Self::Error> {
+        match dir {
+            Dir::North => Ok(North),
+            Dir::East => Ok(East),
+            Dir::South => Ok(South),
+            Dir::West => Ok(West),
+            _ => Err(dir),
+        }
+    }
+}
+
+impl TryFrom<char> for CardinalDir {
+    type Error = DirParseError;
+
+    /// Parses U/D/L/R, N/S/E/W or ^/v/</>
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'U' | 'N' | '^' => Ok(North),
+            'R' | 'E' | '>' => Ok(East),
+            'D' | 'S' | 'v' => Ok(South),
+            'L' | 'W' | '<' => Ok(West),
+            _ => Err(DirParseError(c.to_string())),
+        }
+    }
+}
+
+impl TryFrom<u8> for CardinalDir {
+    type Error = DirParseError;
+
+    /// Parses U/D/L/R, N/S/E/W or ^/v/</>
+    fn try_from(c: u8) -> Result<Self, Self::Error> {
+        Self::try_from(char::from(c))
+    }
+}
+
+impl std::str::FromStr for CardinalDir {
+    type Err = DirParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Self::try_from(c),
+            _ => Err(DirParseError(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rotate() {
+        assert_eq!(North.rotate(1), East);
+        assert_eq!(North.rotate(-1), West);
+        assert_eq!(East.rotate(6), West);
+        assert_eq!(South.rotate(-7), West);
+        for dir in CardinalDir::all() {
+            assert_eq!(dir.rotate(4), dir);
+            assert_eq!(dir.rotate(0), dir);
+            assert_eq!(dir.turn_right().turn_left(), dir);
+            assert_eq!(dir.turn_around(), dir.rotate(2));
+            assert_eq!(Dir8::from(dir).rotate(2), Dir8::from(dir.turn_right()));
+        }
+    }
+
+    #[test]
+    fn parse() {
+        for (chars, dir) in [("UN^", North), ("RE>", East), ("DSv", South), ("LW<", West)] {
+            for c in chars.chars() {
+                assert_eq!(CardinalDir::try_from(c), Ok(dir));
+                assert_eq!(CardinalDir::try_from(c as u8), Ok(dir));
+                assert_eq!(c.to_string().parse(), Ok(dir));
+            }
+        }
+        assert_eq!(
+            CardinalDir::try_from('V'),
+            Err(DirParseError("V".to_string()))
+        );
+        assert_eq!(
+            CardinalDir::try_from(b'.'),
+            Err(DirParseError(".".to_string()))
+        );
+        assert_eq!(
+            "NE".parse::<CardinalDir>(),
+            Err(DirParseError("NE".to_string()))
+        );
+        assert_eq!(CardinalDir::try_from(Dir8::SouthWest), Err(Dir8::SouthWest));
+        assert_eq!(CardinalDir::try_from(Dir::None), Err(Dir::None));
+    }
+}
@@ -0,0 +1,24 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::Stencil;
+use num::*;
+
+/// Which neighbors are connected to a position
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Connectivity {
+    /// North, east, south and west
+    Four,
+    /// Also the diagonals
+    Eight,
+}
+
+impl Connectivity {
+    pub fn stencil<T: Signed + Copy>(self) -> Stencil<T> {
+        match self {
+            Connectivity::Four => Stencil::cardinal(),
+            Connectivity::Eight => Stencil::eight(),
+        }
+    }
+}
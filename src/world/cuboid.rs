@@ -0,0 +1,150 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::{Dir3, LengthType, Point3};
+use num::*;
+
+/// An axis aligned box
+///
+/// Both min and max are part of the box.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Cuboid<T: LengthType> {
+    pub min: Point3<T>,
+    pub max: Point3<T>,
+}
+
+impl<T: LengthType> Cuboid<T> {
+    /// The box with corners a and b, in any order
+    pub fn new(a: Point3<T>, b: Point3<T>) -> Self {
+        Self {
+            min: Point3::new(a.x.min(b.x), a.y.min(b.y), a.z.min(b.z)),
+            max: Point3::new(a.x.max(b.x), a.y.max(b.y), a.z.max(b.z)),
+        }
+    }
+
+    pub fn size(&self) -> Point3<T> {
+        self.max - self.min + Point3::new(One::one(), One::one(), One::one())
+    }
+
+    pub fn volume(&self) -> T {
+        let size = self.size();
+        size.x * size.y * size.z
+    }
+
+    pub fn contains(&self, pos: Point3<T>) -> bool {
+        self.min.x <= pos.x
+            && pos.x <= self.max.x
+            && self.min.y <= pos.y
+            && pos.y <= self.max.y
+            && self.min.z <= pos.z
+            && pos.z <= self.max.z
+    }
+
+    /// Do the boxes share any point?
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && self.max.x >= other.min.x
+            && self.min.y <= other.max.y
+            && self.max.y >= other.min.y
+            && self.min.z <= other.max.z
+            && self.max.z >= other.min.z
+    }
+
+    /// The points that are in both boxes
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        if self.overlaps(other) {
+            Some(Self {
+                min: Point3::new(
+                    self.min.x.max(other.min.x),
+                    self.min.y.max(other.min.y),
+                    self.min.z.max(other.min.z),
+                ),
+                max: Point3::new(
+                    self.max.x.min(other.max.x),
+                    self.max.y.min(other.max.y),
+                    self.max.z.min(other.max.z),
+                ),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// The smallest box that contains both boxes
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: Point3::new(
+                self.min.x.min(other.min.x),
+                self.min.y.min(other.min.y),
+                self.min.z.min(other.min.z),
+            ),
+            max: Point3::new(
+                self.max.x.max(other.max.x),
+                self.max.y.max(other.max.y),
+                self.max.z.max(other.max.z),
+            ),
+        }
+    }
+
+    pub fn translate(&self, offset: Point3<T>) -> Self {
+        Self {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+
+    /// The box moved one step in dir
+    pub fn walk(&self, dir: Dir3) -> Self {
+        self.translate(Point3::from(dir))
+    }
+
+    /// All points in the box, x changes fastest and z slowest
+    pub fn points(&self) -> impl Iterator<Item = Point3<T>> {
+        let Self { min, max } = *self;
+        range_inclusive(min.z, max.z).flat_map(move |z| {
+            range_inclusive(min.y, max.y)
+                .flat_map(move |y| range_inclusive(min.x, max.x).map(move |x| Point3::new(x, y, z)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn cuboid(a: (i32, i32, i32), b: (i32, i32, i32)) -> Cuboid<i32> {
+        Cuboid::new(Point3::new(a.0, a.1, a.2), Point3::new(b.0, b.1, b.2))
+    }
+
+    #[test]
+    fn new_orders_corners() {
+        let c = cuboid((2, 0, 5), (0, 3, 1));
+        assert_eq!(c, cuboid((0, 0, 1), (2, 3, 5)));
+        assert_eq!(c.volume(), 3 * 4 * 5);
+    }
+
+    #[test]
+    fn touching_boxes_overlap() {
+        let a = cuboid((0, 0, 0), (2, 2, 2));
+        let b = cuboid((2, 2, 2), (3, 3, 3));
+        assert!(a.overlaps(&b));
+        assert_eq!(a.intersection(&b), Some(cuboid((2, 2, 2), (2, 2, 2))));
+    }
+
+    #[test]
+    fn separate_boxes_dont_overlap() {
+        let a = cuboid((0, 0, 0), (2, 2, 2));
+        let b = cuboid((0, 0, 3), (2, 2, 3));
+        assert!(!a.overlaps(&b));
+        assert_eq!(a.intersection(&b), None);
+        assert!(b.walk(Dir3::Down).overlaps(&a));
+    }
+
+    #[test]
+    fn points_covers_volume() {
+        let c = cuboid((-1, 0, 0), (1, 1, 2));
+        assert_eq!(c.points().count() as i32, c.volume());
+        assert!(c.points().all(|p| c.contains(p)));
+    }
+}
@@ -37,6 +37,7 @@ impl std::fmt::Display for Dir {
 }
 
 impl Dir {
+    /// Turns 45 degrees clockwise
     pub fn turn_right(self) -> Self {
         use Dir::*;
         match self {
@@ -52,6 +53,7 @@ impl Dir {
         }
     }
 
+    /// Turns 45 degrees counterclockwise
     pub fn turn_left(self) -> Self {
         use Dir::*;
         match self {
@@ -67,27 +69,7 @@ impl Dir {
         }
     }
 
-    pub fn turn_cardinal_left(self) -> Self {
-        use Dir::*;
-        match self {
-            None => self,
-            North => West,
-            South => East,
-            East => North,
-            West => South,
-            _ => panic!("Direction {} is invalid", self),
-        }
-    }
-
-    pub fn turn_cardinal_right(self) -> Self {
-        use Dir::*;
-        match self {
-            None => self,
-            North => East,
-            South => West,
-            East => South,
-            West => North,
-            _ => panic!("Direction {} is invalid", self),
-        }
+    pub fn opposite(self) -> Self {
+        self.turn_right().turn_right().turn_right().turn_right()
     }
 }
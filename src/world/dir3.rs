@@ -0,0 +1,54 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// The six directions along the axes
+///
+/// North and south are along y, with north being towards smaller y,
+/// just like Dir. East is towards larger x and up towards larger z.
+#[derive(Hash, Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Dir3 {
+    North,
+    South,
+    East,
+    West,
+    Up,
+    Down,
+}
+
+impl std::fmt::Display for Dir3 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Dir3::*;
+        write!(
+            f,
+            "{}",
+            match self {
+                North => "North",
+                South => "South",
+                East => "East",
+                West => "West",
+                Up => "Up",
+                Down => "Down",
+            }
+        )
+    }
+}
+
+impl Dir3 {
+    pub fn all() -> [Self; 6] {
+        use Dir3::*;
+        [North, South, East, West, Up, Down]
+    }
+
+    pub fn opposite(self) -> Self {
+        use Dir3::*;
+        match self {
+            North => South,
+            South => North,
+            East => West,
+            West => East,
+            Up => Down,
+            Down => Up,
+        }
+    }
+}
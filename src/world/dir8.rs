@@ -0,0 +1,187 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::{CardinalDir, Dir, DirParseError};
+
+/// One of the eight directions to a neighbor, including the diagonals
+#[derive(Hash, Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Dir8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+use Dir8::*;
+
+impl Dir8 {
+    /// In clockwise order
+    const ALL: [Self; 8] = [
+        North, NorthEast, East, SouthEast, South, SouthWest, West, NorthWest,
+    ];
+
+    pub fn all() -> impl Iterator<Item = Self> {
+        Self::ALL.into_iter()
+    }
+
+    pub fn cardinals() -> impl Iterator<Item = Self> {
+        Self::ALL.into_iter().step_by(2)
+    }
+
+    pub fn diagonals() -> impl Iterator<Item = Self> {
+        Self::ALL.into_iter().skip(1).step_by(2)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        self as usize % 2 == 1
+    }
+
+    /// Turns 45 degrees eighth_turns times, clockwise for positive numbers
+    pub fn rotate(self, eighth_turns: i32) -> Self {
+        let turns = usize::try_from(eighth_turns.rem_euclid(8)).expect("Non negative number");
+        Self::ALL[(self as usize + turns) % 8]
+    }
+
+    /// Turns 45 degrees clockwise
+    pub fn turn_right(self) -> Self {
+        self.rotate(1)
+    }
+
+    /// Turns 45 degrees counterclockwise
+    pub fn turn_left(self) -> Self {
+        self.rotate(-1)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotate(4)
+    }
+
+    /// The direction after a U-turn, same as opposite
+    pub fn turn_around(self) -> Self {
+        self.opposite()
+    }
+}
+
+impl std::fmt::Display for Dir8 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Dir::from(*self).fmt(f)
+    }
+}
+
+impl From<Dir8> for Dir {
+    fn from(dir: Dir8) -> Self {
+        match dir {
+            North => Dir::North,
+            NorthEast => Dir::NorthEast,
+            East => Dir::East,
+            SouthEast => Dir::SouthEast,
+            South => Dir::South,
+            SouthWest => Dir::SouthWest,
+            West => Dir::West,
+            NorthWest => Dir::NorthWest,
+        }
+    }
+}
+
+impl TryFrom<Dir> for Dir8 {
+    /// Dir::None
+    type Error = Dir;
+
+    fn try_from(dir: Dir) -> Result<Self, Self::Error> {
+        match dir {
+            Dir::None => Err(dir),
+            Dir::North => Ok(North),
+            Dir::NorthEast => Ok(NorthEast),
+            Dir::East => Ok(East),
+            Dir::SouthEast => Ok(SouthEast),
+            Dir::South => Ok(South),
+            Dir::SouthWest => Ok(SouthWest),
+            Dir::West => Ok(West),
+            Dir::NorthWest => Ok(NorthWest),
+        }
+    }
+}
+
+impl TryFrom<char> for Dir8 {
+    type Error = DirParseError;
+
+    /// Parses the single character cardinal directions, as CardinalDir
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        CardinalDir::try_from(c).map(Self::from)
+    }
+}
+
+impl TryFrom<u8> for Dir8 {
+    type Error = DirParseError;
+
+    /// Parses the single character cardinal directions, as CardinalDir
+    fn try_from(c: u8) -> Result<Self, Self::Error> {
+        Self::try_from(char::from(c))
+    }
+}
+
+impl std::str::FromStr for Dir8 {
+    type Err = DirParseError;
+
+    /// Parses compass points such as N, NE or sw, or any single character
+    /// that CardinalDir parses
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_uppercase().as_str() {
+            "NE" => Ok(NorthEast),
+            "SE" => Ok(SouthEast),
+            "SW" => Ok(SouthWest),
+            "NW" => Ok(NorthWest),
+            _ => {
+                let mut chars = s.chars();
+                match (chars.next(), chars.next()) {
+                    // Only the compass letters, as 'v' is south but 'V' isn't
+                    (Some(c), None) if "nesw".contains(c) => Self::try_from(c.to_ascii_uppercase()),
+                    (Some(c), None) => Self::try_from(c),
+                    _ => Err(DirParseError(s.to_string())),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rotate() {
+        assert_eq!(North.rotate(1), NorthEast);
+        assert_eq!(North.rotate(-1), NorthWest);
+        assert_eq!(West.rotate(3), NorthEast);
+        assert_eq!(SouthEast.rotate(-10), NorthEast);
+        for dir in Dir8::all() {
+            assert_eq!(dir.rotate(8), dir);
+            assert_eq!(dir.turn_right().turn_left(), dir);
+            assert_eq!(dir.opposite().opposite(), dir);
+            assert_eq!(dir.opposite().is_diagonal(), dir.is_diagonal());
+        }
+        assert!(Dir8::diagonals().all(|dir| dir.is_diagonal()));
+        assert!(Dir8::cardinals().all(|dir| !dir.is_diagonal()));
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Dir8::try_from('N'), Ok(North));
+        assert_eq!(Dir8::try_from(b'<'), Ok(West));
+        assert_eq!("ne".parse(), Ok(NorthEast));
+        assert_eq!("SW".parse(), Ok(SouthWest));
+        assert_eq!("e".parse(), Ok(East));
+        assert_eq!("NNE".parse::<Dir8>(), Err(DirParseError("NNE".to_string())));
+        assert_eq!(Dir8::try_from('x'), Err(DirParseError("x".to_string())));
+        for c in "v^<>NESWUDLRV".chars() {
+            let parsed = c.to_string().parse::<Dir8>();
+            assert_eq!(parsed, Dir8::try_from(c));
+            assert_eq!(parsed, CardinalDir::try_from(c).map(Dir8::from));
+        }
+    }
+}
@@ -0,0 +1,15 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// The text that couldn't be parsed as a direction
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DirParseError(pub String);
+
+impl std::fmt::Display for DirParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\" is not a direction", self.0)
+    }
+}
+
+impl std::error::Error for DirParseError {}
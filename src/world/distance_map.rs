@@ -0,0 +1,183 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::{LengthType, Point};
+use num::*;
+
+/// The number of steps needed to reach every position of a Map
+///
+/// Created by Map::distance_field.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DistanceMap<T: LengthType>
+where
+    usize: TryFrom<T>,
+    <usize as TryFrom<T>>::Error: std::fmt::Debug,
+{
+    data: Vec<Option<u32>>,
+    width: T,
+    height: T,
+}
+
+impl<T: LengthType> DistanceMap<T>
+where
+    usize: TryFrom<T>,
+    <usize as TryFrom<T>>::Error: std::fmt::Debug,
+{
+    pub(super) fn new(width: T, height: T) -> Self {
+        let mut data = Vec::new();
+        data.resize(
+            usize::try_from(width * height).expect("Positive size"),
+            None,
+        );
+        Self {
+            data,
+            width,
+            height,
+        }
+    }
+
+    pub fn get_width(&self) -> T {
+        self.width
+    }
+
+    pub fn get_height(&self) -> T {
+        self.height
+    }
+
+    fn is_inside_map(&self, pos: Point<T>) -> bool {
+        pos.x >= Zero::zero() && pos.y >= Zero::zero() && pos.x < self.width && pos.y < self.height
+    }
+
+    fn get_index_for(&self, pos: Point<T>) -> usize {
+        usize::try_from(pos.x + pos.y * self.width).expect("Positive index")
+    }
+
+    /// The distance to pos, None if it can't be reached or is outside of the map
+    pub fn get(&self, pos: Point<T>) -> Option<u32> {
+        if self.is_inside_map(pos) {
+            self.data[self.get_index_for(pos)]
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn set(&mut self, pos: Point<T>, distance: u32) {
+        let index = self.get_index_for(pos);
+        self.data[index] = Some(distance);
+    }
+
+    /// All reachable positions with their distance
+    pub fn iter(&self) -> impl Iterator<Item = (Point<T>, u32)> + '_ {
+        let mut pos = Point::<T> {
+            x: Zero::zero(),
+            y: Zero::zero(),
+        };
+        self.data.iter().filter_map(move |distance| {
+            let current = pos;
+            pos.x += One::one();
+            if pos.x >= self.width {
+                pos.x = Zero::zero();
+                pos.y += One::one();
+            }
+            distance.map(|distance| (current, distance))
+        })
+    }
+
+    /// Positions exactly steps away
+    pub fn at_distance(&self, steps: u32) -> impl Iterator<Item = Point<T>> + '_ {
+        self.iter()
+            .filter_map(move |(pos, distance)| (distance == steps).then_some(pos))
+    }
+
+    /// Positions at most steps away
+    pub fn within(&self, steps: u32) -> impl Iterator<Item = Point<T>> + '_ {
+        self.iter()
+            .filter_map(move |(pos, distance)| (distance <= steps).then_some(pos))
+    }
+
+    /// Positions that can be at after exactly steps, when it is allowed to
+    /// walk back and forth.
+    ///
+    /// That is the positions at most steps away with the same parity as steps.
+    pub fn within_with_parity(&self, steps: u32) -> impl Iterator<Item = Point<T>> + '_ {
+        self.iter().filter_map(move |(pos, distance)| {
+            (distance <= steps && distance % 2 == steps % 2).then_some(pos)
+        })
+    }
+
+    /// The largest distance to any reachable position
+    pub fn max_distance(&self) -> Option<u32> {
+        self.data.iter().flatten().copied().max()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::super::Map;
+    use super::*;
+    use hashbrown::HashSet;
+
+    const GARDEN: &str = "...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........
+";
+
+    /// The plots reachable in exactly steps, one step at a time
+    fn walk(map: &Map, steps: u32) -> HashSet<Point<i32>> {
+        let mut plots: HashSet<_> = map.find(b'S').into_iter().collect();
+        for _ in 0..steps {
+            plots = plots
+                .iter()
+                .flat_map(|pos| pos.neighbors4())
+                .filter(|&(pos, _)| matches!(map.get_at(pos), Some(b'.' | b'S')))
+                .map(|(pos, _)| pos)
+                .collect();
+        }
+        plots
+    }
+
+    #[test]
+    fn distances_match_walking() {
+        let map = Map::from_string(GARDEN);
+        let distances = map.distance_field(&map.find(b'S'), |_, c| c == b'.');
+        for steps in 0..12 {
+            let walked = walk(&map, steps);
+            let within: HashSet<_> = distances.within_with_parity(steps).collect();
+            assert_eq!(within, walked, "After {} steps", steps);
+            assert!(distances
+                .at_distance(steps)
+                .all(|pos| walked.contains(&pos)));
+        }
+        assert_eq!(distances.within_with_parity(6).count(), 16);
+        assert_eq!(
+            distances.within(2).count(),
+            1 + distances.at_distance(1).count() + distances.at_distance(2).count()
+        );
+    }
+
+    #[test]
+    fn unreachable_positions() {
+        let map = Map::<i32>::from_string("S.#.\n..#.\n###.\n");
+        let distances = map.distance_field(&[Point { x: 0, y: 0 }], |_, c| c == b'.');
+        assert_eq!(distances.get(Point { x: 1, y: 1 }), Some(2));
+        assert_eq!(distances.get(Point { x: 3, y: 0 }), None);
+        assert_eq!(distances.get(Point { x: 2, y: 0 }), None);
+        assert_eq!(distances.get(Point { x: -1, y: 0 }), None);
+        assert_eq!(distances.within(100).count(), 4);
+        assert_eq!(distances.within_with_parity(100).count(), 2);
+        assert_eq!(distances.max_distance(), Some(2));
+
+        let walled = Map::<i32>::from_string("S#\n#.\n");
+        let distances = walled.distance_field(&[], |_, c| c == b'.');
+        assert_eq!(distances.max_distance(), None);
+    }
+}
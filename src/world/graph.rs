@@ -0,0 +1,308 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use hashbrown::HashMap;
+use num::*;
+use rayon::prelude::*;
+
+mod cut;
+mod dot;
+pub use cut::Cut;
+
+/// A directed graph with weighted edges
+///
+/// Every node is added once and is then referred to by its id, which is
+/// its index in the order the nodes were added.
+#[derive(Debug, Clone)]
+pub struct Graph<N, W = u32> {
+    nodes: Vec<N>,
+    ids: HashMap<N, usize>,
+    edges: Vec<Vec<(usize, W)>>,
+}
+
+impl<N: Clone + Eq + std::hash::Hash, W: Copy> Default for Graph<N, W> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Clone + Eq + std::hash::Hash, W: Copy> Graph<N, W> {
+    pub fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            ids: HashMap::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// The number of nodes
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The id of node, which is added if it wasn't already
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.edges.push(Vec::new());
+        id
+    }
+
+    pub fn id(&self, node: &N) -> Option<usize> {
+        self.ids.get(node).copied()
+    }
+
+    pub fn node(&self, id: usize) -> &N {
+        &self.nodes[id]
+    }
+
+    /// All nodes, indexed by their ids
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn add_edge(&mut self, from: usize, to: usize, weight: W) {
+        self.edges[from].push((to, weight));
+    }
+
+    /// Adds edges in both directions
+    pub fn add_undirected_edge(&mut self, a: usize, b: usize, weight: W) {
+        self.add_edge(a, b, weight);
+        self.add_edge(b, a, weight);
+    }
+
+    /// The nodes that can be reached from from, with the weights of the edges
+    pub fn edges(&self, from: usize) -> &[(usize, W)] {
+        &self.edges[from]
+    }
+}
+
+impl<N: Clone + Eq + std::hash::Hash + Sync, W: Num + Ord + Copy + Send + Sync> Graph<N, W> {
+    /// The length of the longest path from start to goal that visits no node twice
+    ///
+    /// Returns None if goal can't be reached. The graph can have at most
+    /// 128 nodes.
+    pub fn longest_path(&self, start: usize, goal: usize) -> Option<W> {
+        let solver = LongestPath::new(self, goal);
+        solver.solve(start, solver.all & !bit(start), &mut HashMap::new())
+    }
+
+    /// Like longest_path, but also gives the nodes of the path, from start to goal
+    pub fn longest_path_nodes(&self, start: usize, goal: usize) -> Option<(W, Vec<usize>)> {
+        let solver = LongestPath::new(self, goal);
+        let mut memo = HashMap::new();
+        let mut allowed = solver.all & !bit(start);
+        let length = solver.solve(start, allowed, &mut memo)?;
+        let mut path = vec![start];
+        let mut rest = length;
+        let mut node = start;
+        while node != goal {
+            // The next node is one whose own longest path makes up the rest
+            let (next, weight) = self
+                .edges(node)
+                .iter()
+                .copied()
+                .find(|&(next, weight)| {
+                    allowed & bit(next) != 0
+                        && weight <= rest
+                        && solver.solve(next, allowed & !bit(next), &mut memo)
+                            == Some(rest - weight)
+                })
+                .expect("A step of the longest path");
+            allowed &= !bit(next);
+            rest = rest - weight;
+            node = next;
+            path.push(node);
+        }
+        Some((length, path))
+    }
+
+    /// Like longest_path, but searches the branches of the first levels in parallel
+    pub fn par_longest_path(&self, start: usize, goal: usize) -> Option<W> {
+        let solver = LongestPath::new(self, goal);
+        // Paths to search, with their last node, the nodes they may still
+        // visit and their length
+        let mut paths = vec![(start, solver.all & !bit(start), W::zero())];
+        let mut best = None;
+        let wanted = 8 * rayon::current_num_threads();
+        while !paths.is_empty() && paths.len() < wanted {
+            let mut longer = Vec::new();
+            for (node, allowed, length) in paths {
+                if node == goal {
+                    best = best.max(Some(length));
+                    continue;
+                }
+                for &(next, weight) in self.edges(node) {
+                    if allowed & bit(next) != 0 {
+                        longer.push((next, allowed & !bit(next), length + weight));
+                    }
+                }
+            }
+            paths = longer;
+        }
+        let rest = paths
+            .into_par_iter()
+            .filter_map(|(node, allowed, length)| {
+                solver
+                    .solve(node, allowed, &mut HashMap::new())
+                    .map(|rest| length + rest)
+            })
+            .max();
+        best.max(rest)
+    }
+}
+
+/// A set of node ids, one bit per node
+type NodeSet = u128;
+
+fn bit(node: usize) -> NodeSet {
+    assert!(node < NodeSet::BITS as usize, "Too many nodes");
+    1 << node
+}
+
+struct LongestPath<'a, N, W> {
+    graph: &'a Graph<N, W>,
+    goal: usize,
+    all: NodeSet,
+    neighbors: Vec<NodeSet>,
+}
+
+impl<'a, N: Clone + Eq + std::hash::Hash, W: Num + Ord + Copy> LongestPath<'a, N, W> {
+    fn new(graph: &'a Graph<N, W>, goal: usize) -> Self {
+        let neighbors = (0..graph.len())
+            .map(|node| {
+                graph
+                    .edges(node)
+                    .iter()
+                    .fold(0, |set, &(next, _)| set | bit(next))
+            })
+            .collect();
+        Self {
+            graph,
+            goal,
+            all: (0..graph.len()).fold(0, |set, node| set | bit(node)),
+            neighbors,
+        }
+    }
+
+    /// The nodes in allowed that can be reached from node through allowed nodes
+    fn reachable(&self, node: usize, allowed: NodeSet) -> NodeSet {
+        let mut reached = 0;
+        let mut frontier = self.neighbors[node] & allowed;
+        while frontier != 0 {
+            reached |= frontier;
+            let mut next = 0;
+            let mut nodes = frontier;
+            while nodes != 0 {
+                let node = nodes.trailing_zeros() as usize;
+                nodes &= nodes - 1;
+                next |= self.neighbors[node];
+            }
+            frontier = next & allowed & !reached;
+        }
+        reached
+    }
+
+    /// The longest path from node to goal through the allowed nodes
+    ///
+    /// Only the allowed nodes that can still be reached matter, so the
+    /// results are remembered in memo by those.
+    fn solve(
+        &self,
+        node: usize,
+        allowed: NodeSet,
+        memo: &mut HashMap<(usize, NodeSet), Option<W>>,
+    ) -> Option<W> {
+        if node == self.goal {
+            return Some(W::zero());
+        }
+        let allowed = self.reachable(node, allowed);
+        if allowed & bit(self.goal) == 0 {
+            return None;
+        }
+        if let Some(&length) = memo.get(&(node, allowed)) {
+            return length;
+        }
+        let mut best = None;
+        for &(next, weight) in self.graph.edges(node) {
+            if allowed & bit(next) != 0 {
+                let length = self
+                    .solve(next, allowed & !bit(next), memo)
+                    .map(|length| length + weight);
+                best = best.max(length);
+            }
+        }
+        memo.insert((node, allowed), best);
+        best
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // A square a-b-c-d-a with a diagonal a-c and a dead end from b to e.
+    fn square() -> Graph<char> {
+        let mut graph = Graph::new();
+        for (a, b, weight) in [
+            ('a', 'b', 1),
+            ('b', 'c', 2),
+            ('c', 'd', 3),
+            ('d', 'a', 4),
+            ('a', 'c', 10),
+            ('b', 'e', 100),
+        ] {
+            let a = graph.add_node(a);
+            let b = graph.add_node(b);
+            graph.add_undirected_edge(a, b, weight);
+        }
+        graph
+    }
+
+    #[test]
+    fn nodes_are_interned() {
+        let mut graph = square();
+        assert_eq!(graph.len(), 5);
+        assert_eq!(graph.add_node('c'), 2);
+        assert_eq!(graph.id(&'e'), Some(4));
+        assert_eq!(graph.edges(1).len(), 3);
+    }
+
+    #[test]
+    fn longest_path_visits_nodes_once() {
+        let graph = square();
+        // a-c-b
+        assert_eq!(graph.longest_path(0, 1), Some(12));
+        // a-c-d, as e is a dead end
+        assert_eq!(graph.longest_path(0, 3), Some(13));
+        assert_eq!(graph.par_longest_path(0, 3), Some(13));
+        // e-b-a-c
+        assert_eq!(graph.longest_path(4, 2), Some(111));
+        let mut graph = graph;
+        let lonely = graph.add_node('f');
+        assert_eq!(graph.longest_path(0, lonely), None);
+    }
+
+    #[test]
+    fn longest_path_nodes() {
+        let mut graph = square();
+        assert_eq!(graph.longest_path_nodes(0, 1), Some((12, vec![0, 2, 1])));
+        assert_eq!(graph.longest_path_nodes(0, 3), Some((13, vec![0, 2, 3])));
+        assert_eq!(
+            graph.longest_path_nodes(4, 2),
+            Some((111, vec![4, 1, 0, 2]))
+        );
+        assert_eq!(graph.longest_path_nodes(2, 2), Some((0, vec![2])));
+        let lonely = graph.add_node('f');
+        assert_eq!(graph.longest_path_nodes(0, lonely), None);
+    }
+}
@@ -0,0 +1,292 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::Graph;
+use hashbrown::HashMap;
+use num::*;
+use std::collections::{BinaryHeap, VecDeque};
+
+/// A split of a graph's nodes into two parts
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Cut<W> {
+    /// The total weight of the edges between the parts
+    pub weight: W,
+    /// The edges from the first part to the second
+    pub edges: Vec<(usize, usize)>,
+    pub first: Vec<usize>,
+    pub second: Vec<usize>,
+}
+
+impl<W> Cut<W> {
+    /// The number of nodes in the first and second part
+    pub fn sizes(&self) -> (usize, usize) {
+        (self.first.len(), self.second.len())
+    }
+}
+
+impl<N: Clone + Eq + std::hash::Hash, W: Num + Ord + Copy> Graph<N, W> {
+    /// The cut with the lowest weight, with Stoer-Wagner's algorithm
+    ///
+    /// The graph must be undirected, with every edge added in both
+    /// directions, and have at least two nodes.
+    pub fn min_cut(&self) -> Cut<W> {
+        assert!(self.len() >= 2, "Too few nodes to cut");
+        let mut neighbors: Vec<HashMap<usize, W>> = vec![HashMap::new(); self.len()];
+        for (from, neighbors) in neighbors.iter_mut().enumerate() {
+            for &(to, weight) in self.edges(from) {
+                if to != from {
+                    let sum = neighbors.entry(to).or_insert_with(W::zero);
+                    *sum = *sum + weight;
+                }
+            }
+        }
+        // The nodes that have been merged into each node
+        let mut merged: Vec<Vec<usize>> = (0..self.len()).map(|node| vec![node]).collect();
+        let mut active: Vec<_> = (0..self.len()).collect();
+
+        let mut best: Option<(W, Vec<usize>)> = None;
+        while active.len() > 1 {
+            // Add the most tightly connected node until all are added, the
+            // last one is cut from the rest by the cut of the phase.
+            let mut connection: HashMap<usize, W> =
+                active.iter().map(|&node| (node, W::zero())).collect();
+            let mut queue: BinaryHeap<_> = active.iter().map(|&node| (W::zero(), node)).collect();
+            let mut order = Vec::with_capacity(active.len());
+            while let Some((weight, node)) = queue.pop() {
+                if connection.get(&node) != Some(&weight) {
+                    continue;
+                }
+                connection.remove(&node);
+                order.push((node, weight));
+                for (&next, &edge_weight) in &neighbors[node] {
+                    if let Some(sum) = connection.get_mut(&next) {
+                        *sum = *sum + edge_weight;
+                        queue.push((*sum, next));
+                    }
+                }
+            }
+            let (last, cut_weight) = order[order.len() - 1];
+            let (previous, _) = order[order.len() - 2];
+            if best.as_ref().is_none_or(|(weight, _)| cut_weight < *weight) {
+                best = Some((cut_weight, merged[last].clone()));
+            }
+
+            // Merge last into previous
+            let last_neighbors = std::mem::take(&mut neighbors[last]);
+            for (next, weight) in last_neighbors {
+                neighbors[next].remove(&last);
+                if next != previous {
+                    let sum = neighbors[previous].entry(next).or_insert_with(W::zero);
+                    *sum = *sum + weight;
+                    let sum = neighbors[next].entry(previous).or_insert_with(W::zero);
+                    *sum = *sum + weight;
+                }
+            }
+            let last_merged = std::mem::take(&mut merged[last]);
+            merged[previous].extend(last_merged);
+            active.retain(|&node| node != last);
+        }
+        let (_, first) = best.expect("At least one phase");
+        self.cut_from_part(&first)
+    }
+
+    /// The cut with the lowest weight that separates source from sink
+    ///
+    /// Finds the maximum flow with Edmonds-Karp's algorithm, where the
+    /// weights are the capacities of the edges.
+    pub fn min_st_cut(&self, source: usize, sink: usize) -> Cut<W> {
+        let mut flow = Flow::new(self);
+        while flow.augment(source, sink) {}
+        self.cut_from_part(&flow.reachable(source))
+    }
+
+    /// Finds a cut with a weight of at most max_weight
+    ///
+    /// Every other node is tried as the sink of a flow from the first node,
+    /// but each flow is only increased until it is too large.
+    /// Returns None if there is no such cut.
+    pub fn k_edge_cut(&self, max_weight: W) -> Option<Cut<W>> {
+        let source = 0;
+        (1..self.len()).find_map(|sink| {
+            let mut flow = Flow::new(self);
+            while flow.total <= max_weight {
+                if !flow.augment(source, sink) {
+                    return Some(self.cut_from_part(&flow.reachable(source)));
+                }
+            }
+            None
+        })
+    }
+
+    fn cut_from_part(&self, first: &[usize]) -> Cut<W> {
+        let mut in_first = vec![false; self.len()];
+        for &node in first {
+            in_first[node] = true;
+        }
+        let mut weight = W::zero();
+        let mut edges = Vec::new();
+        for &from in first {
+            for &(to, edge_weight) in self.edges(from) {
+                if !in_first[to] {
+                    weight = weight + edge_weight;
+                    edges.push((from, to));
+                }
+            }
+        }
+        let mut first = first.to_vec();
+        first.sort_unstable();
+        Cut {
+            weight,
+            edges,
+            first,
+            second: (0..self.len()).filter(|&node| !in_first[node]).collect(),
+        }
+    }
+}
+
+/// The residual network of a flow
+struct Flow<W> {
+    /// The head of every arc, every arc is followed by its reverse
+    heads: Vec<usize>,
+    /// The remaining capacity of every arc
+    capacities: Vec<W>,
+    /// The arcs from every node
+    arcs: Vec<Vec<usize>>,
+    total: W,
+}
+
+impl<W: Num + Ord + Copy> Flow<W> {
+    fn new<N: Clone + Eq + std::hash::Hash>(graph: &Graph<N, W>) -> Self {
+        let mut flow = Self {
+            heads: Vec::new(),
+            capacities: Vec::new(),
+            arcs: vec![Vec::new(); graph.len()],
+            total: W::zero(),
+        };
+        for from in 0..graph.len() {
+            for &(to, weight) in graph.edges(from) {
+                flow.arcs[from].push(flow.heads.len());
+                flow.heads.push(to);
+                flow.capacities.push(weight);
+                flow.arcs[to].push(flow.heads.len());
+                flow.heads.push(from);
+                flow.capacities.push(W::zero());
+            }
+        }
+        flow
+    }
+
+    /// Sends flow along the shortest path with capacity left
+    ///
+    /// Returns false if there is no such path.
+    fn augment(&mut self, source: usize, sink: usize) -> bool {
+        // The arc used to reach every node
+        let mut reached_by = vec![None; self.arcs.len()];
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            if node == sink {
+                break;
+            }
+            for &arc in &self.arcs[node] {
+                let next = self.heads[arc];
+                if next != source && reached_by[next].is_none() && self.capacities[arc] > W::zero()
+                {
+                    reached_by[next] = Some(arc);
+                    queue.push_back(next);
+                }
+            }
+        }
+        if reached_by[sink].is_none() {
+            return false;
+        }
+
+        let path: Vec<usize> =
+            std::iter::successors(reached_by[sink], |&arc| reached_by[self.heads[arc ^ 1]])
+                .collect();
+        let amount = path
+            .iter()
+            .map(|&arc| self.capacities[arc])
+            .min()
+            .expect("A path has arcs");
+        for arc in path {
+            self.capacities[arc] = self.capacities[arc] - amount;
+            self.capacities[arc ^ 1] = self.capacities[arc ^ 1] + amount;
+        }
+        self.total = self.total + amount;
+        true
+    }
+
+    /// The nodes that can be reached from source through arcs with capacity left
+    fn reachable(&self, source: usize) -> Vec<usize> {
+        let mut reached = vec![false; self.arcs.len()];
+        reached[source] = true;
+        let mut to_visit = vec![source];
+        while let Some(node) = to_visit.pop() {
+            for &arc in &self.arcs[node] {
+                let next = self.heads[arc];
+                if !reached[next] && self.capacities[arc] > W::zero() {
+                    reached[next] = true;
+                    to_visit.push(next);
+                }
+            }
+        }
+        (0..reached.len()).filter(|&node| reached[node]).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Two squares with diagonals, joined by two edges.
+    fn two_squares() -> Graph<u8> {
+        let mut graph = Graph::new();
+        for square in [0, 4] {
+            for a in square..square + 4 {
+                for b in a + 1..square + 4 {
+                    let (a, b) = (graph.add_node(a), graph.add_node(b));
+                    graph.add_undirected_edge(a, b, 1);
+                }
+            }
+        }
+        graph.add_undirected_edge(0, 4, 1);
+        graph.add_undirected_edge(3, 6, 1);
+        graph
+    }
+
+    #[test]
+    fn min_cut_splits_squares() {
+        let graph = two_squares();
+        let cut = graph.min_cut();
+        assert_eq!(cut.weight, 2);
+        assert_eq!(cut.sizes(), (4, 4));
+        let mut edges: Vec<_> = cut
+            .edges
+            .iter()
+            .map(|&(a, b)| (a.min(b), a.max(b)))
+            .collect();
+        edges.sort();
+        assert_eq!(edges, vec![(0, 4), (3, 6)]);
+    }
+
+    #[test]
+    fn st_cut_follows_weights() {
+        let mut graph = two_squares();
+        let cut = graph.min_st_cut(1, 5);
+        assert_eq!(cut.weight, 2);
+        assert_eq!(cut.first, vec![0, 1, 2, 3]);
+        // Cutting off the source is cheaper than cutting the heavy bridges
+        graph.add_undirected_edge(0, 4, 10);
+        let cut = graph.min_st_cut(1, 5);
+        assert_eq!(cut.weight, 3);
+        assert_eq!(cut.first, vec![1]);
+    }
+
+    #[test]
+    fn k_edge_cut_is_limited() {
+        let graph = two_squares();
+        assert_eq!(graph.k_edge_cut(1), None);
+        assert_eq!(graph.k_edge_cut(2).map(|cut| cut.sizes()), Some((4, 4)));
+    }
+}
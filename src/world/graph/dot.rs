@@ -0,0 +1,175 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::Graph;
+use hashbrown::HashSet;
+use num::*;
+use std::fmt::{Display, Write};
+
+/// The directory export_dot writes to
+const DOT_DIR_VARIABLE: &str = "AOC_DOT_DIR";
+
+impl<N, W> Graph<N, W>
+where
+    N: Clone + Eq + std::hash::Hash + Display,
+    W: Num + Copy + Display,
+{
+    /// The graph in Graphviz's DOT language
+    ///
+    /// The nodes are labelled with their values and the edges with their
+    /// weights, unless all weights are one. The highlighted edges, for
+    /// example the edges of a Cut or the steps of a path, are drawn in red,
+    /// as are both of their nodes.
+    /// If every edge has a reverse edge with the same weight, the graph is
+    /// drawn as undirected.
+    pub fn to_dot(&self, name: &str, highlight: &[(usize, usize)]) -> String {
+        let undirected = self.is_undirected();
+        let mut highlighted: HashSet<_> = highlight.iter().copied().collect();
+        if undirected {
+            highlighted.extend(highlight.iter().map(|&(a, b)| (b, a)));
+        }
+        let highlighted_nodes: HashSet<_> = highlighted.iter().flat_map(|&(a, b)| [a, b]).collect();
+        let weighted = (0..self.len())
+            .flat_map(|from| self.edges(from))
+            .any(|&(_, weight)| !weight.is_one());
+
+        let mut dot = String::new();
+        let (kind, arrow) = if undirected {
+            ("graph", "--")
+        } else {
+            ("digraph", "->")
+        };
+        writeln!(dot, "{} {} {{", kind, quoted(name)).expect("String is writable");
+        for (id, node) in self.nodes().iter().enumerate() {
+            write!(dot, "    n{} [label={}", id, quoted(&node.to_string()))
+                .expect("String is writable");
+            if highlighted_nodes.contains(&id) {
+                write!(dot, ", color=red").expect("String is writable");
+            }
+            writeln!(dot, "];").expect("String is writable");
+        }
+        for from in 0..self.len() {
+            for &(to, weight) in self.edges(from) {
+                if undirected && to < from {
+                    continue;
+                }
+                let mut attributes = Vec::new();
+                if weighted {
+                    attributes.push(format!("label=\"{}\"", weight));
+                }
+                if highlighted.contains(&(from, to)) {
+                    attributes.push("color=red, penwidth=3".to_string());
+                }
+                write!(dot, "    n{} {} n{}", from, arrow, to).expect("String is writable");
+                if !attributes.is_empty() {
+                    write!(dot, " [{}]", attributes.join(", ")).expect("String is writable");
+                }
+                writeln!(dot, ";").expect("String is writable");
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Writes to_dot's result to name.dot, if AOC_DOT_DIR tells where
+    ///
+    /// Does nothing if AOC_DOT_DIR isn't set. The solvers don't call it; the
+    /// days' export_dot tests do, so
+    /// `AOC_DOT_DIR=dir cargo test export_dot` writes all their graphs.
+    pub fn export_dot(&self, name: &str, highlight: &[(usize, usize)]) -> std::io::Result<()> {
+        match std::env::var_os(DOT_DIR_VARIABLE) {
+            Some(dir) => {
+                let path = std::path::Path::new(&dir).join(format!("{}.dot", name));
+                std::fs::write(path, self.to_dot(name, highlight))
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn is_undirected(&self) -> bool {
+        (0..self.len()).all(|from| {
+            self.edges(from).iter().all(|&(to, weight)| {
+                self.edges(to)
+                    .iter()
+                    .any(|&(back, back_weight)| back == from && back_weight == weight)
+            })
+        })
+    }
+}
+
+/// text as a DOT string, where only quotes and backslashes are escaped
+fn quoted(text: &str) -> String {
+    let mut quoted = String::with_capacity(text.len() + 2);
+    quoted.push('"');
+    for c in text.chars() {
+        if c == '"' || c == '\\' {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn undirected_graph_with_highlight() {
+        let mut graph = Graph::new();
+        let a = graph.add_node("a");
+        let b = graph.add_node("b\"");
+        let c = graph.add_node("c");
+        graph.add_undirected_edge(a, b, 1);
+        graph.add_undirected_edge(b, c, 1);
+        assert_eq!(
+            graph.to_dot("g", &[(c, b)]),
+            "graph \"g\" {\n    n0 [label=\"a\"];\n    n1 [label=\"b\\\"\", color=red];\n    \
+             n2 [label=\"c\", color=red];\n    n0 -- n1;\n    n1 -- n2 [color=red, penwidth=3];\n}\n"
+        );
+    }
+
+    #[test]
+    fn directed_graph_with_weights() {
+        let mut graph = Graph::new();
+        let a = graph.add_node(1);
+        let b = graph.add_node(2);
+        graph.add_edge(a, b, 5);
+        assert_eq!(
+            graph.to_dot("d", &[]),
+            "digraph \"d\" {\n    n0 [label=\"1\"];\n    n1 [label=\"2\"];\n    \
+             n0 -> n1 [label=\"5\"];\n}\n"
+        );
+    }
+
+    #[test]
+    fn directed_graph_with_highlight() {
+        let mut graph = Graph::new();
+        let a = graph.add_node("a");
+        let b = graph.add_node("b");
+        let c = graph.add_node("c");
+        graph.add_edge(a, b, 1);
+        graph.add_edge(b, c, 1);
+        graph.add_edge(c, a, 1);
+        assert_eq!(
+            graph.to_dot("d", &[(a, b)]),
+            "digraph \"d\" {\n    n0 [label=\"a\", color=red];\n    \
+             n1 [label=\"b\", color=red];\n    n2 [label=\"c\"];\n    \
+             n0 -> n1 [color=red, penwidth=3];\n    n1 -> n2;\n    n2 -> n0;\n}\n"
+        );
+    }
+
+    #[test]
+    fn labels_are_dot_escaped() {
+        let mut graph = Graph::<_, u32>::new();
+        graph.add_node("a\\b");
+        graph.add_node("tab\there é");
+        assert_eq!(
+            graph.to_dot("x\"y", &[]),
+            "graph \"x\\\"y\" {\n    n0 [label=\"a\\\\b\"];\n    \
+             n1 [label=\"tab\there é\"];\n}\n"
+        );
+    }
+}
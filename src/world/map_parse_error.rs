@@ -0,0 +1,62 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+/// Why a string couldn't be turned into a Map
+///
+/// Line and column numbers start at 1.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MapParseError {
+    Empty,
+    RaggedRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    NonAscii {
+        line: usize,
+        column: usize,
+    },
+    UnknownCell {
+        line: usize,
+        column: usize,
+        c: char,
+    },
+    TooLarge {
+        width: usize,
+        height: usize,
+    },
+}
+
+impl std::fmt::Display for MapParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use MapParseError::*;
+        match self {
+            Empty => write!(f, "The map is empty"),
+            RaggedRow {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Line {} is {} characters long, expected {}",
+                line, found, expected
+            ),
+            NonAscii { line, column } => {
+                write!(f, "Non-ASCII character at line {}, column {}", line, column)
+            }
+            UnknownCell { line, column, c } => write!(
+                f,
+                "Unknown character '{}' at line {}, column {}",
+                c, line, column
+            ),
+            TooLarge { width, height } => write!(
+                f,
+                "A {}x{} map is too large for its length type",
+                width, height
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MapParseError {}
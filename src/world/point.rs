@@ -4,7 +4,7 @@
 
 type Length = i32;
 
-use super::dir::Dir;
+use super::{CardinalDir, Dir, Dir8};
 use num::*;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
@@ -14,59 +14,288 @@ pub struct Point<T = Length> {
 }
 
 impl<T: Signed + Copy> Point<T> {
-    pub fn walk(self, dir: Dir) -> Self {
-        match dir {
-            Dir::None => Self {
-                x: self.x,
-                y: self.y,
-            },
-            Dir::North => Self {
-                x: self.x,
-                y: self.y - One::one(),
-            },
-            Dir::South => Self {
-                x: self.x,
-                y: self.y + One::one(),
-            },
-            Dir::East => Self {
-                x: self.x + One::one(),
-                y: self.y,
-            },
-            Dir::West => Self {
-                x: self.x - One::one(),
-                y: self.y,
-            },
-            Dir::NorthEast => Self {
-                x: self.x + One::one(),
-                y: self.y - One::one(),
-            },
-            Dir::NorthWest => Self {
-                x: self.x - One::one(),
-                y: self.y - One::one(),
-            },
-            Dir::SouthEast => Self {
-                x: self.x + One::one(),
-                y: self.y + One::one(),
-            },
-            Dir::SouthWest => Self {
-                x: self.x - One::one(),
-                y: self.y + One::one(),
-            },
-        }
+    pub fn walk(self, dir: impl Into<Dir>) -> Self {
+        self + Self::from(dir.into())
     }
 
     pub fn manhattan_distance(&self, other: Self) -> T {
         T::abs(&(self.x - other.x)) + T::abs(&(self.y - other.y))
     }
-}
 
-impl std::ops::Add for Point {
-    type Output = Self;
+    /// The distance squared, as the crow flies
+    pub fn squared_euclidean_distance(&self, other: Self) -> T {
+        let diff = *self - other;
+        diff.x * diff.x + diff.y * diff.y
+    }
+
+    /// The four points north, east, south and west of this one
+    pub fn neighbors4(self) -> impl Iterator<Item = (Self, CardinalDir)> {
+        CardinalDir::all().map(move |dir| (self.walk(dir), dir))
+    }
+
+    /// The eight points around this one, clockwise from north
+    pub fn neighbors8(self) -> impl Iterator<Item = (Self, Dir8)> {
+        Dir8::all().map(move |dir| (self.walk(dir), dir))
+    }
+
+    /// Rotates the point 90 degrees clockwise around origin
+    pub fn rotate_right(self, origin: Self) -> Self {
+        let diff = self - origin;
+        origin
+            + Self {
+                x: -diff.y,
+                y: diff.x,
+            }
+    }
+
+    /// Rotates the point 90 degrees counterclockwise around origin
+    pub fn rotate_left(self, origin: Self) -> Self {
+        let diff = self - origin;
+        origin
+            + Self {
+                x: diff.y,
+                y: -diff.x,
+            }
+    }
 
-    fn add(self, other: Self) -> Self {
+    /// Reflects the point in the vertical line through column x
+    pub fn reflect_x(self, x: T) -> Self {
         Self {
-            x: self.x + other.x,
-            y: self.y + other.y,
+            x: x + x - self.x,
+            y: self.y,
         }
     }
+
+    /// Reflects the point in the horizontal line through row y
+    pub fn reflect_y(self, y: T) -> Self {
+        Self {
+            x: self.x,
+            y: y + y - self.y,
+        }
+    }
+
+    /// Reflects the point in a vertical mirror between column x - 1 and x
+    pub fn mirror_x(self, x: T) -> Self {
+        Self {
+            x: x + x - T::one() - self.x,
+            y: self.y,
+        }
+    }
+
+    /// Reflects the point in a horizontal mirror between row y - 1 and y
+    pub fn mirror_y(self, y: T) -> Self {
+        Self {
+            x: self.x,
+            y: y + y - T::one() - self.y,
+        }
+    }
+}
+
+impl<T: Signed + Copy + PartialOrd> Point<T> {
+    pub fn chebyshev_distance(&self, other: Self) -> T {
+        let dx = T::abs(&(self.x - other.x));
+        let dy = T::abs(&(self.y - other.y));
+        if dx > dy {
+            dx
+        } else {
+            dy
+        }
+    }
+
+    /// The direction to walk to reach other
+    ///
+    /// Gives Dir::None if other is this point and None if other isn't on
+    /// the same row, column or diagonal.
+    pub fn direction_to(&self, other: Self) -> Option<Dir> {
+        let diff = other - *self;
+        if diff.x != T::zero() && diff.y != T::zero() && diff.x.abs() != diff.y.abs() {
+            return None;
+        }
+        let step = Self {
+            x: diff.x.signum(),
+            y: diff.y.signum(),
+        };
+        let origin = Self {
+            x: T::zero(),
+            y: T::zero(),
+        };
+        Some(
+            origin
+                .neighbors8()
+                .find(|&(pos, _)| pos == step)
+                .map_or(Dir::None, |(_, dir)| dir.into()),
+        )
+    }
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for Point<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl<T: Signed> From<Dir> for Point<T> {
+    /// The vector of one step in dir
+    fn from(dir: Dir) -> Self {
+        let (x, y) = match dir {
+            Dir::None => (T::zero(), T::zero()),
+            Dir::North => (T::zero(), -T::one()),
+            Dir::South => (T::zero(), T::one()),
+            Dir::East => (T::one(), T::zero()),
+            Dir::West => (-T::one(), T::zero()),
+            Dir::NorthEast => (T::one(), -T::one()),
+            Dir::NorthWest => (-T::one(), -T::one()),
+            Dir::SouthEast => (T::one(), T::one()),
+            Dir::SouthWest => (-T::one(), T::one()),
+        };
+        Self { x, y }
+    }
+}
+
+impl<T: Signed> From<CardinalDir> for Point<T> {
+    fn from(dir: CardinalDir) -> Self {
+        Self::from(Dir::from(dir))
+    }
+}
+
+impl<T: Signed> From<Dir8> for Point<T> {
+    fn from(dir: Dir8) -> Self {
+        Self::from(Dir::from(dir))
+    }
+}
+
+impl_point_ops!(Point, x, y);
+
+impl<T: Signed + Copy, D: Into<Dir>> std::ops::Add<D> for Point<T> {
+    type Output = Self;
+
+    fn add(self, dir: D) -> Self {
+        self.walk(dir)
+    }
+}
+
+impl<T: Signed + Copy + std::ops::AddAssign, D: Into<Dir>> std::ops::AddAssign<D> for Point<T> {
+    fn add_assign(&mut self, dir: D) {
+        *self += Self::from(dir.into());
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = Point { x: 3, y: -2 };
+        let b = Point { x: -1, y: 5 };
+        assert_eq!(a + b, Point { x: 2, y: 3 });
+        assert_eq!(a - b, Point { x: 4, y: -7 });
+        assert_eq!(-a, Point { x: -3, y: 2 });
+        assert_eq!(a * -2, Point { x: -6, y: 4 });
+        let mut c = a;
+        c += b;
+        assert_eq!(c, a + b);
+        c -= b;
+        assert_eq!(c, a);
+    }
+
+    #[test]
+    fn from_dir() {
+        assert_eq!(Point::<i32>::from(Dir::None), Point { x: 0, y: 0 });
+        assert_eq!(Point::<i32>::from(Dir::North), Point { x: 0, y: -1 });
+        assert_eq!(Point::<i32>::from(Dir::SouthWest), Point { x: -1, y: 1 });
+        assert_eq!(Point::<i64>::from(CardinalDir::East), Point { x: 1, y: 0 });
+        assert_eq!(Point::<i64>::from(Dir8::NorthWest), Point { x: -1, y: -1 });
+    }
+
+    #[test]
+    fn add_dir() {
+        let pos = Point { x: 5, y: 5 };
+        assert_eq!(pos + Dir::None, pos);
+        assert_eq!(pos + Dir::South, Point { x: 5, y: 6 });
+        assert_eq!(pos + CardinalDir::West, Point { x: 4, y: 5 });
+        assert_eq!(pos + Dir8::NorthEast, Point { x: 6, y: 4 });
+        assert_eq!(pos + Dir::East, pos.walk(Dir::East));
+        let mut walked = pos;
+        walked += Dir::None;
+        assert_eq!(walked, pos);
+        walked += CardinalDir::North;
+        walked += Dir8::SouthEast;
+        assert_eq!(walked, Point { x: 6, y: 5 });
+    }
+
+    #[test]
+    fn distances() {
+        let a = Point { x: 1, y: -2 };
+        let b = Point { x: -3, y: 1 };
+        assert_eq!(a.manhattan_distance(b), 7);
+        assert_eq!(a.chebyshev_distance(b), 4);
+        assert_eq!(b.chebyshev_distance(a), 4);
+        assert_eq!(a.squared_euclidean_distance(b), 25);
+        assert_eq!(a.chebyshev_distance(a), 0);
+    }
+
+    #[test]
+    fn neighbors() {
+        let pos = Point { x: 2, y: 3 };
+        let four: Vec<_> = pos.neighbors4().collect();
+        assert_eq!(four.len(), 4);
+        assert!(four.iter().all(|&(n, dir)| n == pos + dir));
+        assert!(four.iter().all(|&(n, _)| pos.manhattan_distance(n) == 1));
+        let eight: Vec<_> = pos.neighbors8().collect();
+        assert_eq!(eight.len(), 8);
+        assert_eq!(eight[0], (Point { x: 2, y: 2 }, Dir8::North));
+        assert_eq!(eight[1], (Point { x: 3, y: 2 }, Dir8::NorthEast));
+        assert!(eight.iter().all(|&(n, _)| pos.chebyshev_distance(n) == 1));
+    }
+
+    #[test]
+    fn rotations() {
+        let origin = Point { x: 1, y: 1 };
+        let pos = Point { x: 4, y: 2 };
+        assert_eq!(pos.rotate_right(origin), Point { x: 0, y: 4 });
+        assert_eq!(pos.rotate_left(origin), Point { x: 2, y: -2 });
+        assert_eq!(pos.rotate_right(origin).rotate_left(origin), pos);
+        let half = pos.rotate_right(origin).rotate_right(origin);
+        assert_eq!(half, pos.rotate_left(origin).rotate_left(origin));
+        assert_eq!(half, origin - (pos - origin));
+        let full = (0..4).fold(pos, |p, _| p.rotate_right(origin));
+        assert_eq!(full, pos);
+        assert_eq!(origin.rotate_right(origin), origin);
+        // North turns into east, as for the directions
+        let north = Point::from(Dir::North);
+        let zero = Point { x: 0, y: 0 };
+        assert_eq!(north.rotate_right(zero), Point::from(Dir::East));
+        assert_eq!(north.rotate_left(zero), Point::from(Dir::West));
+    }
+
+    #[test]
+    fn reflections() {
+        let pos = Point { x: 4, y: -2 };
+        assert_eq!(pos.reflect_x(1), Point { x: -2, y: -2 });
+        assert_eq!(pos.reflect_y(1), Point { x: 4, y: 4 });
+        assert_eq!(pos.reflect_x(4), pos);
+        assert_eq!(pos.reflect_x(7).reflect_x(7), pos);
+        assert_eq!(pos.reflect_y(-5).reflect_y(-5), pos);
+        // Mirrors lie between cells, so no cell maps onto itself
+        assert_eq!(Point { x: 2, y: 0 }.mirror_x(3), Point { x: 3, y: 0 });
+        assert_eq!(Point { x: 0, y: 5 }.mirror_y(3), Point { x: 0, y: 0 });
+        assert_eq!(pos.mirror_x(2).mirror_x(2), pos);
+        assert_eq!(pos.mirror_y(2).mirror_y(2), pos);
+        assert_ne!(pos.mirror_x(4), pos);
+    }
+
+    #[test]
+    fn direction_to() {
+        let pos = Point { x: 2, y: 2 };
+        assert_eq!(pos.direction_to(pos), Some(Dir::None));
+        assert_eq!(pos.direction_to(Point { x: 2, y: -5 }), Some(Dir::North));
+        assert_eq!(pos.direction_to(Point { x: 9, y: 2 }), Some(Dir::East));
+        assert_eq!(
+            pos.direction_to(Point { x: -1, y: 5 }),
+            Some(Dir::SouthWest)
+        );
+        assert_eq!(pos.direction_to(Point { x: 4, y: 0 }), Some(Dir::NorthEast));
+        assert_eq!(pos.direction_to(Point { x: 3, y: 4 }), None);
+    }
 }
@@ -0,0 +1,85 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+type Length = i32;
+
+use super::dir3::Dir3;
+use num::*;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Point3<T = Length> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Signed + Copy> Point3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self { x, y, z }
+    }
+
+    /// The point one step away in dir
+    pub fn walk(self, dir: Dir3) -> Self {
+        self + Self::from(dir)
+    }
+
+    pub fn manhattan_distance(&self, other: Self) -> T {
+        T::abs(&(self.x - other.x)) + T::abs(&(self.y - other.y)) + T::abs(&(self.z - other.z))
+    }
+}
+
+impl<T: Signed> From<Dir3> for Point3<T> {
+    /// The unit vector in dir
+    fn from(dir: Dir3) -> Self {
+        let (x, y, z) = match dir {
+            Dir3::North => (T::zero(), -T::one(), T::zero()),
+            Dir3::South => (T::zero(), T::one(), T::zero()),
+            Dir3::East => (T::one(), T::zero(), T::zero()),
+            Dir3::West => (-T::one(), T::zero(), T::zero()),
+            Dir3::Up => (T::zero(), T::zero(), T::one()),
+            Dir3::Down => (T::zero(), T::zero(), -T::one()),
+        };
+        Self { x, y, z }
+    }
+}
+
+impl_point_ops!(Point3, x, y, z);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let a = Point3::new(1, -2, 3);
+        let b = Point3::new(4, 5, -6);
+        assert_eq!(a + b, Point3::new(5, 3, -3));
+        assert_eq!(a - b, Point3::new(-3, -7, 9));
+        assert_eq!(-a, Point3::new(-1, 2, -3));
+        assert_eq!(a * 3, Point3::new(3, -6, 9));
+        let mut c = a;
+        c += b;
+        assert_eq!(c, a + b);
+        c -= b;
+        assert_eq!(c, a);
+        assert_eq!(a.manhattan_distance(b), 3 + 7 + 9);
+    }
+
+    #[test]
+    fn walk() {
+        let origin = Point3::<i32>::new(0, 0, 0);
+        assert_eq!(origin.walk(Dir3::Up), Point3::new(0, 0, 1));
+        assert_eq!(origin.walk(Dir3::North), Point3::new(0, -1, 0));
+        for dir in [
+            Dir3::North,
+            Dir3::South,
+            Dir3::East,
+            Dir3::West,
+            Dir3::Up,
+            Dir3::Down,
+        ] {
+            assert_eq!(origin.walk(dir).manhattan_distance(origin), 1);
+        }
+    }
+}
@@ -0,0 +1,71 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::{LengthType, Point};
+use num::*;
+
+/// A summary of a connected area of a map
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct Region<T> {
+    /// The number of cells
+    pub area: usize,
+    /// The smallest x and y of the cells
+    pub min: Point<T>,
+    /// The largest x and y of the cells
+    pub max: Point<T>,
+    /// The number of cell sides that face something outside of the region
+    pub perimeter: usize,
+    /// The number of straight sides of the outline, holes included
+    pub sides: usize,
+    /// If any cell is on the outermost rows or columns of the map
+    pub touches_edge: bool,
+}
+
+impl<T: LengthType> Region<T> {
+    /// Summarizes cells, which must not be empty
+    ///
+    /// is_member tells if a position inside the map is part of the region.
+    pub(super) fn from_cells<F>(cells: &[Point<T>], width: T, height: T, is_member: F) -> Self
+    where
+        F: Fn(Point<T>) -> bool,
+    {
+        let is_inside = |pos: Point<T>| {
+            pos.x >= Zero::zero() && pos.y >= Zero::zero() && pos.x < width && pos.y < height
+        };
+        let is_member = |pos: Point<T>| is_inside(pos) && is_member(pos);
+        let mut region = Self {
+            area: cells.len(),
+            min: cells[0],
+            max: cells[0],
+            perimeter: 0,
+            sides: 0,
+            touches_edge: false,
+        };
+        for &pos in cells {
+            region.min = Point {
+                x: region.min.x.min(pos.x),
+                y: region.min.y.min(pos.y),
+            };
+            region.max = Point {
+                x: region.max.x.max(pos.x),
+                y: region.max.y.max(pos.y),
+            };
+            for (new_pos, dir) in pos.neighbors4() {
+                if is_member(new_pos) {
+                    continue;
+                }
+                region.perimeter += 1;
+                if !is_inside(new_pos) {
+                    region.touches_edge = true;
+                }
+                // Only count the side at its first cell, when coming from the left
+                let prev = pos.walk(dir.turn_left());
+                if !is_member(prev) || is_member(prev.walk(dir)) {
+                    region.sides += 1;
+                }
+            }
+        }
+        region
+    }
+}
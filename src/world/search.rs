@@ -0,0 +1,239 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use hashbrown::HashMap;
+use num::*;
+use std::collections::BinaryHeap;
+
+/// A problem that can be solved with `search`.
+///
+/// With the default heuristic the search is Dijkstra's algorithm, with an
+/// admissible heuristic it is A*.
+pub trait SearchProblem {
+    type State: Clone + Eq + std::hash::Hash;
+    type Cost: Num + Ord + Copy;
+
+    fn start_states(&self) -> Vec<Self::State>;
+
+    /// Pushes all states reachable from state, together with the cost of
+    /// moving there, to successors.
+    fn successors(&self, state: &Self::State, successors: &mut Vec<(Self::State, Self::Cost)>);
+
+    fn is_goal(&self, state: &Self::State) -> bool;
+
+    /// Must never overestimate the remaining cost to a goal
+    fn heuristic(&self, _state: &Self::State) -> Self::Cost {
+        Zero::zero()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct Node<C, S> {
+    estimated_cost: C,
+    cost: C,
+    state: S,
+    parent: Option<S>,
+}
+
+impl<C: Ord, S: Eq> Ord for Node<C, S> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        other.estimated_cost.cmp(&self.estimated_cost)
+    }
+}
+
+impl<C: Ord, S: Eq> PartialOrd for Node<C, S> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+type Expanded<P> = HashMap<
+    <P as SearchProblem>::State,
+    (
+        <P as SearchProblem>::Cost,
+        Option<<P as SearchProblem>::State>,
+    ),
+>;
+
+type GoalNode<P> = Node<<P as SearchProblem>::Cost, <P as SearchProblem>::State>;
+
+/// Runs A* until a goal is found.
+///
+/// Returns the goal node and all expanded states with their cost and parent state.
+fn find_goal<P: SearchProblem>(problem: &P) -> (Option<GoalNode<P>>, Expanded<P>) {
+    let mut expanded: Expanded<P> = HashMap::new();
+    let mut to_expand = BinaryHeap::new();
+    for state in problem.start_states() {
+        to_expand.push(Node {
+            estimated_cost: problem.heuristic(&state),
+            cost: Zero::zero(),
+            state,
+            parent: None,
+        });
+    }
+
+    let mut successors = Vec::new();
+    while let Some(node) = to_expand.pop() {
+        if problem.is_goal(&node.state) {
+            return (Some(node), expanded);
+        }
+        let Node {
+            cost,
+            state,
+            parent,
+            ..
+        } = node;
+        if let Some((old_cost, _)) = expanded.get(&state) {
+            if *old_cost <= cost {
+                continue;
+            }
+        }
+        problem.successors(&state, &mut successors);
+        for (new_state, step_cost) in successors.drain(..) {
+            let new_cost = cost + step_cost;
+            if expanded
+                .get(&new_state)
+                .is_some_and(|&(old_cost, _)| old_cost <= new_cost)
+            {
+                continue;
+            }
+            to_expand.push(Node {
+                estimated_cost: new_cost + problem.heuristic(&new_state),
+                cost: new_cost,
+                state: new_state,
+                parent: Some(state.clone()),
+            });
+        }
+        expanded.insert(state, (cost, parent));
+    }
+    (None, expanded)
+}
+
+/// Finds the cheapest way from any start state to a goal state
+///
+/// Returns the cost and the goal state that was reached,
+/// or None if no goal can be reached.
+pub fn search<P: SearchProblem>(problem: &P) -> Option<(P::Cost, P::State)> {
+    find_goal(problem)
+        .0
+        .map(|Node { cost, state, .. }| (cost, state))
+}
+
+/// Finds the cheapest way from any start state to a goal state
+///
+/// Returns the cost and all states on the way, starting with the start state
+/// and ending with the goal state, or None if no goal can be reached.
+pub fn search_path<P: SearchProblem>(problem: &P) -> Option<(P::Cost, Vec<P::State>)> {
+    let (goal, expanded) = find_goal(problem);
+    let Node {
+        cost,
+        state,
+        mut parent,
+        ..
+    } = goal?;
+    let mut path = vec![state];
+    while let Some(state) = parent {
+        parent = expanded.get(&state).expect("Parent is expanded").1.clone();
+        path.push(state);
+    }
+    path.reverse();
+    Some((cost, path))
+}
+
+/// Finds the cheapest cost to every state that can be reached from the start states
+/// for at most max_cost.
+///
+/// The goal test and heuristic of the problem are not used.
+pub fn reachable_within<P: SearchProblem>(
+    problem: &P,
+    max_cost: P::Cost,
+) -> HashMap<P::State, P::Cost> {
+    let mut expanded = HashMap::new();
+    let mut to_expand = BinaryHeap::new();
+    for state in problem.start_states() {
+        to_expand.push(Node {
+            estimated_cost: Zero::zero(),
+            cost: Zero::zero(),
+            state,
+            parent: None,
+        });
+    }
+
+    let mut successors = Vec::new();
+    while let Some(Node { cost, state, .. }) = to_expand.pop() {
+        if expanded.contains_key(&state) {
+            continue;
+        }
+        problem.successors(&state, &mut successors);
+        for (new_state, step_cost) in successors.drain(..) {
+            let new_cost = cost + step_cost;
+            if new_cost <= max_cost && !expanded.contains_key(&new_state) {
+                to_expand.push(Node {
+                    estimated_cost: new_cost,
+                    cost: new_cost,
+                    state: new_state,
+                    parent: None,
+                });
+            }
+        }
+        expanded.insert(state, cost);
+    }
+    expanded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Walk along the number line, either one step for cost 1 or
+    // double the number for cost 3.
+    struct NumberLine(i32);
+
+    impl SearchProblem for NumberLine {
+        type State = i32;
+        type Cost = u32;
+
+        fn start_states(&self) -> Vec<i32> {
+            vec![1]
+        }
+
+        fn successors(&self, n: &i32, successors: &mut Vec<(i32, u32)>) {
+            if *n < self.0 {
+                successors.push((n + 1, 1));
+                successors.push((n * 2, 3));
+            }
+        }
+
+        fn is_goal(&self, n: &i32) -> bool {
+            *n == self.0
+        }
+    }
+
+    #[test]
+    fn search_finds_cheapest() {
+        // 1 -> 2 -> 4 -> 8 -> 16 -> 17
+        assert_eq!(search(&NumberLine(17)), Some((10, 17)));
+    }
+
+    #[test]
+    fn search_path_finds_cheapest() {
+        assert_eq!(
+            search_path(&NumberLine(17)),
+            Some((10, vec![1, 2, 3, 4, 8, 16, 17]))
+        );
+    }
+
+    #[test]
+    fn search_unreachable() {
+        assert_eq!(search(&NumberLine(0)), None);
+    }
+
+    #[test]
+    fn reachable_within_limit() {
+        let reached = reachable_within(&NumberLine(100), 3);
+        assert_eq!(reached.get(&4), Some(&3));
+        assert_eq!(reached.get(&2), Some(&1));
+        assert!(!reached.contains_key(&8));
+    }
+}
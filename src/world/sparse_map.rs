@@ -0,0 +1,214 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::{Dir, Length, LengthType, Map, Point};
+use hashbrown::HashMap;
+use num::*;
+
+/// A map without fixed size where only some positions have values
+///
+/// Keeps track of the smallest box that contains all positions with values.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SparseMap<T: LengthType = Length, V = u8> {
+    data: HashMap<Point<T>, V>,
+    bounds: Option<(Point<T>, Point<T>)>,
+}
+
+impl<T: LengthType, V: Copy> Default for SparseMap<T, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: LengthType, V: Copy> SparseMap<T, V> {
+    pub fn new() -> Self {
+        Self {
+            data: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// The smallest and largest x and y of all positions with values
+    pub fn bounding_box(&self) -> Option<(Point<T>, Point<T>)> {
+        self.bounds
+    }
+
+    pub fn get_at(&self, pos: Point<T>) -> Option<V> {
+        self.data.get(&pos).copied()
+    }
+
+    pub fn set_at(&mut self, pos: Point<T>, val: V) {
+        self.data.insert(pos, val);
+        self.bounds = Some(match self.bounds {
+            None => (pos, pos),
+            Some((min, max)) => (
+                Point {
+                    x: min.x.min(pos.x),
+                    y: min.y.min(pos.y),
+                },
+                Point {
+                    x: max.x.max(pos.x),
+                    y: max.y.max(pos.y),
+                },
+            ),
+        });
+    }
+
+    pub fn remove_at(&mut self, pos: Point<T>) -> Option<V> {
+        let val = self.data.remove(&pos)?;
+        if let Some((min, max)) = self.bounds {
+            if pos.x == min.x || pos.y == min.y || pos.x == max.x || pos.y == max.y {
+                self.update_bounds();
+            }
+        }
+        Some(val)
+    }
+
+    fn update_bounds(&mut self) {
+        self.bounds = self.data.keys().fold(None, |bounds, &pos| match bounds {
+            None => Some((pos, pos)),
+            Some((min, max)) => Some((
+                Point {
+                    x: min.x.min(pos.x),
+                    y: min.y.min(pos.y),
+                },
+                Point {
+                    x: max.x.max(pos.x),
+                    y: max.y.max(pos.y),
+                },
+            )),
+        });
+    }
+
+    /// All positions with values, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (Point<T>, V)> + '_ {
+        self.data.iter().map(|(&pos, &val)| (pos, val))
+    }
+
+    /// The neighbors of pos that have values
+    pub fn neighbors(&self, pos: Point<T>) -> impl Iterator<Item = (Point<T>, Dir, V)> + '_ {
+        pos.neighbors8()
+            .filter_map(|(pos, dir)| Some((pos, dir.into(), self.get_at(pos)?)))
+    }
+
+    pub fn find(&self, needle: V) -> Vec<Point<T>>
+    where
+        V: PartialEq,
+    {
+        self.iter()
+            .filter_map(|(p, c)| if c == needle { Some(p) } else { None })
+            .collect()
+    }
+}
+
+impl<T: LengthType, V: Copy + Into<u8>> SparseMap<T, V>
+where
+    usize: TryFrom<T>,
+    <usize as TryFrom<T>>::Error: std::fmt::Debug,
+{
+    /// A Map of the area between from and to (not included)
+    ///
+    /// Position from is at (0, 0) in the new map.
+    /// Positions without values are set to empty.
+    pub fn to_map_area(&self, from: Point<T>, to: Point<T>, empty: u8) -> Map<T> {
+        let mut map = Map::new(to.x - from.x, to.y - from.y);
+        for y in range(Zero::zero(), map.get_height()) {
+            for x in range(Zero::zero(), map.get_width()) {
+                let val = self.get_at(Point { x, y } + from);
+                map.set_at(Point { x, y }, val.map_or(empty, Into::into));
+            }
+        }
+        map
+    }
+
+    /// A Map of the bounding box, with its smallest position at (0, 0)
+    pub fn to_map(&self, empty: u8) -> Map<T> {
+        match self.bounds {
+            Some((min, max)) => self.to_map_area(min, max + Point::from(Dir::SouthEast), empty),
+            None => Map::new(Zero::zero(), Zero::zero()),
+        }
+    }
+
+    pub fn print_with_overlay<F>(&self, mut f: F)
+    where
+        F: FnMut(Point<T>, u8) -> Option<u8>,
+    {
+        if let Some((min, _)) = self.bounds {
+            self.to_map(b'.')
+                .print_with_overlay(|pos, c| f(pos + min, c));
+        }
+    }
+
+    pub fn print(&self) {
+        self.print_with_overlay(|_, _| None);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn values_and_bounds() {
+        let mut map = SparseMap::<i32, u8>::new();
+        assert!(map.is_empty());
+        assert_eq!(map.bounding_box(), None);
+
+        map.set_at(Point { x: 2, y: -1 }, b'a');
+        map.set_at(Point { x: -3, y: 4 }, b'b');
+        map.set_at(Point { x: 0, y: 0 }, b'c');
+        map.set_at(Point { x: 0, y: 0 }, b'd');
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get_at(Point { x: 0, y: 0 }), Some(b'd'));
+        assert_eq!(map.get_at(Point { x: 1, y: 0 }), None);
+        assert_eq!(
+            map.bounding_box(),
+            Some((Point { x: -3, y: -1 }, Point { x: 2, y: 4 }))
+        );
+
+        // Removing a position on the edge shrinks the box
+        assert_eq!(map.remove_at(Point { x: -3, y: 4 }), Some(b'b'));
+        assert_eq!(map.remove_at(Point { x: -3, y: 4 }), None);
+        assert_eq!(
+            map.bounding_box(),
+            Some((Point { x: 0, y: -1 }, Point { x: 2, y: 0 }))
+        );
+        assert_eq!(map.find(b'a'), vec![Point { x: 2, y: -1 }]);
+        // Clockwise from north, like Point::neighbors8
+        let neighbors: Vec<_> = map.neighbors(Point { x: 1, y: 0 }).collect();
+        assert_eq!(
+            neighbors,
+            vec![
+                (Point { x: 2, y: -1 }, Dir::NorthEast, b'a'),
+                (Point { x: 0, y: 0 }, Dir::West, b'd'),
+            ]
+        );
+
+        map.remove_at(Point { x: 2, y: -1 });
+        map.remove_at(Point { x: 0, y: 0 });
+        assert_eq!(map.bounding_box(), None);
+    }
+
+    #[test]
+    fn to_map() {
+        let mut map = SparseMap::<i32, u8>::new();
+        map.set_at(Point { x: -1, y: 5 }, b'#');
+        map.set_at(Point { x: 1, y: 6 }, b'#');
+        let dense = map.to_map(b'.');
+        assert_eq!(dense.get_width(), 3);
+        assert_eq!(dense.get_height(), 2);
+        assert_eq!(
+            dense.find(b'#'),
+            vec![Point { x: 0, y: 0 }, Point { x: 2, y: 1 }]
+        );
+    }
+}
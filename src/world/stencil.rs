@@ -0,0 +1,115 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::{CardinalDir, Dir8, Length, Point};
+use num::*;
+
+/// The offsets from a position to the positions that count as its neighbors
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Stencil<T = Length> {
+    offsets: Vec<Point<T>>,
+}
+
+impl<T: Signed + Copy> Stencil<T> {
+    pub fn new(offsets: Vec<Point<T>>) -> Self {
+        Self { offsets }
+    }
+
+    /// North, east, south and west
+    pub fn cardinal() -> Self {
+        Self::new(CardinalDir::all().map(Point::from).collect())
+    }
+
+    /// All eight positions around, clockwise from north
+    pub fn eight() -> Self {
+        Self::new(Dir8::all().map(Point::from).collect())
+    }
+
+    /// The moves of a knight in chess
+    pub fn knight() -> Self {
+        let one = T::one();
+        let two = one + one;
+        Self::new(
+            Dir8::diagonals()
+                .map(Point::from)
+                .flat_map(|step: Point<T>| {
+                    [
+                        Point {
+                            x: step.x * two,
+                            y: step.y,
+                        },
+                        Point {
+                            x: step.x,
+                            y: step.y * two,
+                        },
+                    ]
+                })
+                .collect(),
+        )
+    }
+
+    /// The six neighbors of a hexagonal grid in axial coordinates
+    ///
+    /// x is the axis going east and y the one going south-east, so
+    /// north-east is (1, -1) and south-west is (-1, 1). A rectangular map
+    /// then holds a rhombus of hexagons.
+    pub fn hex() -> Self {
+        let (zero, one) = (T::zero(), T::one());
+        Self::new(vec![
+            Point { x: one, y: zero },
+            Point { x: one, y: -one },
+            Point { x: zero, y: -one },
+            Point { x: -one, y: zero },
+            Point { x: -one, y: one },
+            Point { x: zero, y: one },
+        ])
+    }
+
+    pub fn offsets(&self) -> &[Point<T>] {
+        &self.offsets
+    }
+
+    /// The neighbors of pos, in the order of the offsets
+    pub fn around(&self, pos: Point<T>) -> impl Iterator<Item = Point<T>> + '_ {
+        self.offsets.iter().map(move |&offset| pos + offset)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn knight() {
+        let stencil = Stencil::<i32>::knight();
+        assert_eq!(stencil.offsets().len(), 8);
+        for &offset in stencil.offsets() {
+            assert_eq!(offset.manhattan_distance(Point { x: 0, y: 0 }), 3);
+            assert_eq!(offset.chebyshev_distance(Point { x: 0, y: 0 }), 2);
+        }
+        let mut offsets = stencil.offsets().to_vec();
+        offsets.sort();
+        offsets.dedup();
+        assert_eq!(offsets.len(), 8);
+    }
+
+    #[test]
+    fn hex() {
+        let stencil = Stencil::<i32>::hex();
+        assert_eq!(stencil.offsets().len(), 6);
+        let pos = Point { x: 3, y: -2 };
+        assert_eq!(stencil.around(pos).last(), Some(Point { x: 3, y: -1 }));
+        // The offsets cancel out, as opposite neighbors do
+        let sum = stencil
+            .offsets()
+            .iter()
+            .fold(Point { x: 0, y: 0 }, |sum, &offset| sum + offset);
+        assert_eq!(sum, Point { x: 0, y: 0 });
+        // Only one of the diagonals is a neighbor
+        for &offset in stencil.offsets() {
+            assert!(stencil.offsets().contains(&-offset));
+        }
+        assert!(!stencil.offsets().contains(&Point { x: 1, y: 1 }));
+    }
+}
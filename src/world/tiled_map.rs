@@ -0,0 +1,64 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::{Cell, Dir, LengthType, Map, Point};
+
+/// A Map repeated infinitely in all directions
+///
+/// Tile (0, 0) is the map itself, tile (1, 0) is the copy east of it and so on.
+pub struct TiledMap<'a, T: LengthType, C: Cell = u8>
+where
+    usize: TryFrom<T>,
+    <usize as TryFrom<T>>::Error: std::fmt::Debug,
+{
+    map: &'a Map<T, C>,
+}
+
+impl<'a, T: LengthType, C: Cell> TiledMap<'a, T, C>
+where
+    usize: TryFrom<T>,
+    <usize as TryFrom<T>>::Error: std::fmt::Debug,
+{
+    pub fn new(map: &'a Map<T, C>) -> Self {
+        Self { map }
+    }
+
+    pub fn get_map(&self) -> &'a Map<T, C> {
+        self.map
+    }
+
+    /// Which copy of the map pos is in
+    pub fn tile_of(&self, pos: Point<T>) -> Point<T> {
+        Point {
+            x: pos.x.div_euclid(&self.map.get_width()),
+            y: pos.y.div_euclid(&self.map.get_height()),
+        }
+    }
+
+    /// Where in its copy of the map pos is
+    pub fn local_point(&self, pos: Point<T>) -> Point<T> {
+        Point {
+            x: pos.x.rem_euclid(&self.map.get_width()),
+            y: pos.y.rem_euclid(&self.map.get_height()),
+        }
+    }
+
+    /// The position in the given tile that corresponds to local_pos in the map
+    pub fn global_point(&self, tile: Point<T>, local_pos: Point<T>) -> Point<T> {
+        Point {
+            x: tile.x * self.map.get_width() + local_pos.x,
+            y: tile.y * self.map.get_height() + local_pos.y,
+        }
+    }
+
+    pub fn get_at(&self, pos: Point<T>) -> C {
+        self.map.get_at_unchecked(self.local_point(pos))
+    }
+
+    /// All eight neighbors of pos, there is always one in each direction
+    pub fn neighbors(&self, pos: Point<T>) -> impl Iterator<Item = (Point<T>, Dir, C)> + '_ {
+        pos.neighbors8()
+            .map(|(pos, dir)| (pos, dir.into(), self.get_at(pos)))
+    }
+}
@@ -0,0 +1,130 @@
+// SPDX-FileCopyrightText: 2023 Sebastian Andersson <sebastian@bittr.nu>
+//
+// SPDX-License-Identifier: GPL-3.0-or-later
+
+use super::{Cuboid, LengthType, Point3};
+
+/// A dense three dimensional grid covering a Cuboid
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct VoxelMap<T: LengthType, C> {
+    data: Vec<C>,
+    bounds: Cuboid<T>,
+}
+
+impl<T: LengthType, C: Copy> VoxelMap<T, C>
+where
+    usize: TryFrom<T>,
+    <usize as TryFrom<T>>::Error: std::fmt::Debug,
+{
+    pub fn new(bounds: Cuboid<T>, val: C) -> Self {
+        let mut data = Vec::new();
+        data.resize(
+            usize::try_from(bounds.volume()).expect("Positive size"),
+            val,
+        );
+        Self { data, bounds }
+    }
+
+    pub fn get_bounds(&self) -> Cuboid<T> {
+        self.bounds
+    }
+
+    pub fn is_inside_map(&self, pos: Point3<T>) -> bool {
+        self.bounds.contains(pos)
+    }
+
+    fn get_index_for(&self, pos: Point3<T>) -> usize {
+        let size = self.bounds.size();
+        let pos = pos - self.bounds.min;
+        usize::try_from(pos.x + size.x * (pos.y + size.y * pos.z)).expect("Positive index")
+    }
+
+    pub fn get_at_unchecked(&self, pos: Point3<T>) -> C {
+        self.data[self.get_index_for(pos)]
+    }
+
+    pub fn get_at(&self, pos: Point3<T>) -> Option<C> {
+        if self.is_inside_map(pos) {
+            Some(self.get_at_unchecked(pos))
+        } else {
+            None
+        }
+    }
+
+    pub fn set_at(&mut self, pos: Point3<T>, val: C) {
+        assert!(self.is_inside_map(pos), "{pos:?} is outside of the map");
+        let index = self.get_index_for(pos);
+        self.data[index] = val
+    }
+
+    /// Sets all positions in area that are inside the map to val
+    pub fn fill(&mut self, area: &Cuboid<T>, val: C) {
+        if let Some(area) = area.intersection(&self.bounds) {
+            for pos in area.points() {
+                self.set_at(pos, val);
+            }
+        }
+    }
+
+    /// The values of all positions in area that are inside the map
+    pub fn values_in<'a>(&'a self, area: &Cuboid<T>) -> impl Iterator<Item = C> + 'a {
+        area.points().filter_map(|pos| self.get_at(pos))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn map() -> VoxelMap<i32, u8> {
+        VoxelMap::new(
+            Cuboid::new(Point3::new(-1, 0, 2), Point3::new(1, 2, 5)),
+            b'.',
+        )
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut map = map();
+        assert_eq!(map.get_bounds().volume(), 3 * 3 * 4);
+        map.set_at(Point3::new(-1, 0, 2), b'a');
+        map.set_at(Point3::new(1, 2, 5), b'b');
+        assert_eq!(map.get_at(Point3::new(-1, 0, 2)), Some(b'a'));
+        assert_eq!(map.get_at(Point3::new(1, 2, 5)), Some(b'b'));
+        assert_eq!(map.get_at(Point3::new(0, 0, 2)), Some(b'.'));
+        assert_eq!(map.get_at(Point3::new(2, 0, 2)), None);
+        assert_eq!(map.get_at(Point3::new(-1, 0, 6)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "outside of the map")]
+    fn set_outside() {
+        // Would be index 3, the same as (-1, 1, 2), without the check
+        map().set_at(Point3::new(2, 0, 2), b'x');
+    }
+
+    #[test]
+    fn fill_clips_to_the_map() {
+        let mut map = map();
+        map.fill(
+            &Cuboid::new(Point3::new(0, 1, 0), Point3::new(5, 1, 3)),
+            b'#',
+        );
+        let area = Cuboid::new(Point3::new(0, 1, 2), Point3::new(1, 1, 3));
+        assert!(map.values_in(&area).all(|c| c == b'#'));
+        assert_eq!(
+            map.values_in(&map.get_bounds())
+                .filter(|&c| c == b'#')
+                .count(),
+            4
+        );
+        assert_eq!(map.get_at(Point3::new(-1, 1, 2)), Some(b'.'));
+        assert_eq!(map.get_at(Point3::new(-1, 2, 2)), Some(b'.'));
+
+        map.fill(
+            &Cuboid::new(Point3::new(5, 5, 5), Point3::new(6, 6, 6)),
+            b'x',
+        );
+        assert!(map.values_in(&map.get_bounds()).all(|c| c != b'x'));
+    }
+}